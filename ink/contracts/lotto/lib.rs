@@ -1,16 +1,24 @@
 #![cfg_attr(not(feature = "std"), no_std, no_main)]
 
-#[openbrush::implementation(Ownable, AccessControl, Upgradeable)]
+#[openbrush::implementation(Ownable, AccessControl, Upgradeable, PSP34, PSP34Metadata)]
 #[openbrush::contract]
 pub mod lotto_contract {
     use ink::codegen::{EmitEvent, Env};
-    use ink::prelude::vec::Vec;
+    use ink::env::hash::Blake2x256;
+    use ink::prelude::{
+        string::{String, ToString},
+        vec,
+        vec::Vec,
+    };
     use lotto::traits::{
-        config, config::*, error::*, raffle, raffle::*, reward, reward::*, Number, RaffleId,
-        LOTTO_MANAGER_ROLE,
+        config, config::*, error::*, pause, pause::*, quorum, quorum::*, raffle, raffle::*, reward,
+        reward::*, GameId, Number, RaffleId, TicketId, ATTESTOR_ADMIN_ROLE, CONFIG_MANAGER_ROLE,
+        RAFFLE_OPERATOR_ROLE, TREASURER_ROLE,
     };
     use openbrush::contracts::access_control::*;
     use openbrush::contracts::ownable::*;
+    use openbrush::contracts::psp34::extensions::metadata;
+    use openbrush::contracts::psp34::*;
     use openbrush::{modifiers, traits::Storage};
     use phat_rollup_anchor_ink::traits::{
         meta_transaction, meta_transaction::*, rollup_anchor, rollup_anchor::*,
@@ -30,6 +38,24 @@ pub mod lotto_contract {
     /// Event emitted when the raffle is started
     #[ink(event)]
     pub struct RaffleStarted {
+        #[ink(topic)]
+        game_id: GameId,
+        #[ink(topic)]
+        raffle_id: RaffleId,
+    }
+
+    /// Event emitted when a config is scheduled for the next raffle
+    #[ink(event)]
+    pub struct ConfigScheduled {
+        #[ink(topic)]
+        game_id: GameId,
+    }
+
+    /// Event emitted when the scheduled config is applied at the start of a raffle
+    #[ink(event)]
+    pub struct ConfigApplied {
+        #[ink(topic)]
+        game_id: GameId,
         #[ink(topic)]
         raffle_id: RaffleId,
     }
@@ -41,6 +67,23 @@ pub mod lotto_contract {
         raffle_id: RaffleId,
     }
 
+    /// Event emitted when the raffle is cancelled
+    #[ink(event)]
+    pub struct RaffleCancelled {
+        #[ink(topic)]
+        raffle_id: RaffleId,
+    }
+
+    /// Event emitted when a participant is refunded
+    #[ink(event)]
+    pub struct RefundClaimed {
+        #[ink(topic)]
+        raffle_id: RaffleId,
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
     /// Event emitted when the raffle result is received
     #[ink(event)]
     pub struct ResultReceived {
@@ -57,11 +100,48 @@ pub mod lotto_contract {
         winners: Vec<AccountId>,
     }
 
+    /// Event emitted when the winners of a prize tier are rewarded
+    #[ink(event)]
+    pub struct PrizeTierRewarded {
+        #[ink(topic)]
+        raffle_id: RaffleId,
+        nb_matching_numbers: u8,
+        nb_matching_bonus_numbers: u8,
+        winners: Vec<AccountId>,
+        reward: Balance,
+    }
+
+    /// Event emitted when the prize pool is carried over to the next raffle
+    #[ink(event)]
+    pub struct JackpotRolledOver {
+        #[ink(topic)]
+        raffle_id: RaffleId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the expired rewards of a raffle are moved into the next prize pool
+    #[ink(event)]
+    pub struct ExpiredRewardsSwept {
+        #[ink(topic)]
+        raffle_id: RaffleId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the treasury withdraws the fees
+    #[ink(event)]
+    pub struct FeesWithdrawn {
+        #[ink(topic)]
+        treasury: AccountId,
+        amount: Balance,
+    }
+
     /// Event emitted when a reward is pending
     #[ink(event)]
     pub struct PendingReward {
         #[ink(topic)]
         account: AccountId,
+        #[ink(topic)]
+        raffle_id: RaffleId,
         amount: Balance,
     }
 
@@ -70,9 +150,33 @@ pub mod lotto_contract {
     pub struct RewardsClaimed {
         #[ink(topic)]
         account: AccountId,
+        #[ink(topic)]
+        raffle_id: RaffleId,
         amount: Balance,
     }
 
+    /// Event emitted when the vote of an attestor is recorded (M-of-N mode)
+    #[ink(event)]
+    pub struct ResultVoteRecorded {
+        #[ink(topic)]
+        raffle_id: RaffleId,
+        #[ink(topic)]
+        attestor: AccountId,
+        nb_votes: u32,
+    }
+
+    /// Event emitted when a capability is paused
+    #[ink(event)]
+    pub struct Paused {
+        capability: Capability,
+    }
+
+    /// Event emitted when a capability is resumed
+    #[ink(event)]
+    pub struct Unpaused {
+        capability: Capability,
+    }
+
     /// Errors occurred in the contract
     #[derive(Debug, Eq, PartialEq, scale::Encode, scale::Decode)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -80,6 +184,7 @@ pub mod lotto_contract {
         AccessControlError(AccessControlError),
         RaffleError(RaffleError),
         RollupAnchorError(RollupAnchorError),
+        PSP34Error(PSP34Error),
         TransferError,
     }
 
@@ -97,6 +202,13 @@ pub mod lotto_contract {
         }
     }
 
+    /// convertor from PSP34Error to ContractError
+    impl From<PSP34Error> for ContractError {
+        fn from(error: PSP34Error) -> Self {
+            ContractError::PSP34Error(error)
+        }
+    }
+
     /// convertor from RaffleError to ContractError
     impl From<RollupAnchorError> for ContractError {
         fn from(error: RollupAnchorError) -> Self {
@@ -120,6 +232,8 @@ pub mod lotto_contract {
         derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
     )]
     pub struct LottoRequestMessage {
+        /// game id
+        pub game_id: GameId,
         /// raffle id
         pub raffle_id: RaffleId,
         /// request
@@ -136,9 +250,19 @@ pub mod lotto_contract {
         /// arg1: number of numbers for the lotto_draw
         /// arg2:  smallest number for the lotto_draw
         /// arg2:  biggest number for the lotto_draw
-        DrawNumbers(u8, Number, Number),
+        /// arg4:  number of bonus numbers, smallest and biggest bonus numbers (if any)
+        /// arg5:  draw mode (distinct unordered numbers or ordered numbers with repetition)
+        DrawNumbers(u8, Number, Number, Option<(u8, Number, Number)>, DrawMode),
         /// request to check if there is a winner for the given numbers
         CheckWinners(Vec<Number>),
+        /// request to check the winners of each prize tier for the given numbers
+        /// arg1: winning numbers followed by the winning bonus numbers
+        /// arg2: number of winning numbers (without the bonus numbers)
+        /// arg3: number of matching numbers and bonus numbers for each prize tier
+        /// arg4: draw mode used to match the numbers
+        CheckTieredWinners(Vec<Number>, u8, Vec<(u8, u8)>, DrawMode),
+        /// request to draw the seed used to generate the quick-pick tickets of the raffle
+        DrawQuickPickSeed,
     }
 
     /// Message sent to provide the lotto lotto_draw or the list of winners
@@ -161,6 +285,10 @@ pub mod lotto_contract {
         Numbers(Vec<Number>),
         /// list of winners
         Winners(Vec<AccountId>),
+        /// list of winners for each number of matching numbers and bonus numbers
+        TieredWinners(Vec<(u8, u8, Vec<AccountId>)>),
+        /// seed used to generate the quick-pick tickets
+        QuickPickSeed([u8; 32]),
     }
 
     /// Contract storage
@@ -181,39 +309,215 @@ pub mod lotto_contract {
         lotto: raffle::Data,
         #[storage_field]
         reward: reward::Data,
+        #[storage_field]
+        psp34: psp34::Data,
+        #[storage_field]
+        metadata: metadata::Data,
+        #[storage_field]
+        pause: pause::Data,
+        #[storage_field]
+        quorum: quorum::Data,
+    }
+
+    /// default delay (in milliseconds) without status change before a raffle can be cancelled
+    const DEFAULT_CANCELLATION_DELAY: Timestamp = 24 * 60 * 60 * 1000;
+
+    /// maximum number of quick-pick tickets generated in one call
+    const MAX_QUICK_PICK_TICKETS: u32 = 50;
+
+    /// tag prefixing the data signed for `meta_tx_claim`, so that a signature given for another
+    /// meta transaction cannot be replayed as a claim
+    pub const META_TX_CLAIM_TAG: u32 = ink::selector_id!("META_TX_CLAIM");
+
+    /// return the token id of the ticket: the raffle id in the high bits and the ticket id in the low bits
+    pub fn get_ticket_token_id(raffle_id: RaffleId, ticket_id: TicketId) -> Id {
+        Id::U64(((raffle_id as u64) << 32) | ticket_id as u64)
     }
 
     impl RaffleConfig for Contract {}
     impl Raffle for Contract {}
     impl RewardManager for Contract {}
+    impl PauseManager for Contract {}
+    impl AttestorQuorum for Contract {}
 
     impl RollupAnchor for Contract {}
-    impl MetaTransaction for Contract {}
+    impl MetaTransaction for Contract {
+        #[ink(message)]
+        fn meta_tx_rollup_cond_eq(
+            &mut self,
+            request: ForwardRequest,
+            signature: [u8; 65],
+        ) -> Result<bool, RollupAnchorError> {
+            // check the signature and the nonce
+            MetaTransaction::use_meta_tx(self, &request, &signature)?;
+            // only the attestors can sign the messages
+            if !AccessControl::has_role(self, ATTESTOR_ROLE, Some(request.from)) {
+                return Err(ContractError::from(AccessControlError::MissingRole).into());
+            }
+            // decode the data
+            let (conditions, updates, actions): RollupCondEqMethodParams =
+                scale::Decode::decode(&mut request.data.as_slice())
+                    .map_err(|_| RollupAnchorError::FailedToDecode)?;
+            meta_transaction::EventBroadcaster::emit_event_meta_tx_decoded(self);
+            // the responses are handled once the conditions are checked, with the signer as attestor
+            let mut messages = Vec::new();
+            let mut other_actions = Vec::new();
+            for action in actions {
+                match action {
+                    HandleActionInput::Reply(message) => messages.push(message),
+                    action => other_actions.push(action),
+                }
+            }
+            let result =
+                RollupAnchor::inner_rollup_cond_eq(self, conditions, updates, other_actions)?;
+            for message in messages {
+                self.handle_message(message, request.from)?;
+            }
+            Ok(result)
+        }
+    }
 
     impl Contract {
         #[ink(constructor)]
-        pub fn new() -> Self {
+        pub fn new(
+            config_manager: AccountId,
+            raffle_operator: AccountId,
+            treasurer: AccountId,
+            attestor_admin: AccountId,
+        ) -> Self {
             let mut instance = Self::default();
             let caller = instance.env().caller();
             // set the owner of this contract
             ownable::Internal::_init_with_owner(&mut instance, caller);
             // set the admin of this contract
             access_control::Internal::_init_with_admin(&mut instance, Some(caller));
-            // grant the role manager
-            AccessControl::grant_role(&mut instance, LOTTO_MANAGER_ROLE, Some(caller))
-                .expect("Should grant the role LOTTO_MANAGER_ROLE");
+            // grant the roles
+            AccessControl::grant_role(&mut instance, CONFIG_MANAGER_ROLE, Some(config_manager))
+                .expect("Should grant the role CONFIG_MANAGER_ROLE");
+            AccessControl::grant_role(&mut instance, RAFFLE_OPERATOR_ROLE, Some(raffle_operator))
+                .expect("Should grant the role RAFFLE_OPERATOR_ROLE");
+            AccessControl::grant_role(&mut instance, TREASURER_ROLE, Some(treasurer))
+                .expect("Should grant the role TREASURER_ROLE");
+            AccessControl::grant_role(&mut instance, ATTESTOR_ADMIN_ROLE, Some(attestor_admin))
+                .expect("Should grant the role ATTESTOR_ADMIN_ROLE");
+            // the attestor admin manages the attestors
+            access_control::Internal::_set_role_admin(
+                &mut instance,
+                ATTESTOR_ROLE,
+                ATTESTOR_ADMIN_ROLE,
+            );
+            // a raffle can be cancelled only if its status has not changed for one day
+            Raffle::set_cancellation_delay(&mut instance, DEFAULT_CANCELLATION_DELAY)
+                .expect("Should set the cancellation delay");
             instance
         }
 
-        #[ink(message)]
-        pub fn participate(&mut self, numbers: Vec<Number>) -> Result<(), ContractError> {
+        #[ink(message, payable)]
+        pub fn participate(
+            &mut self,
+            game_id: GameId,
+            numbers: Vec<Number>,
+        ) -> Result<(), ContractError> {
+            // register the participation
+            self.inner_participate(game_id, numbers)?;
+            // pay the ticket
+            self.pay_tickets(game_id, 1)?;
+            Ok(())
+        }
+
+        #[ink(message, payable)]
+        pub fn participate_batch(
+            &mut self,
+            game_id: GameId,
+            numbers: Vec<Vec<Number>>,
+        ) -> Result<(), ContractError> {
+            // register the participations
+            let nb_tickets = numbers.len();
+            for n in numbers {
+                self.inner_participate(game_id, n)?;
+            }
+            // pay all tickets
+            self.pay_tickets(game_id, nb_tickets)?;
+            Ok(())
+        }
+
+        /// generate `count` tickets on behalf of the caller from the seed drawn by the offchain rollup
+        #[ink(message, payable)]
+        pub fn participate_quick_pick(
+            &mut self,
+            game_id: GameId,
+            count: u32,
+        ) -> Result<(), ContractError> {
+            // the number of generated tickets is capped (the limits per account still apply)
+            if count == 0 || count > MAX_QUICK_PICK_TICKETS {
+                return Err(RaffleError::IncorrectNbTickets.into());
+            }
+            // get the seed of the current raffle
+            let raffle_id = Raffle::get_current_raffle_id(self, game_id);
+            let seed = Raffle::get_quick_pick_seed(self, raffle_id)
+                .ok_or(RaffleError::QuickPickSeedNotSet)?;
+            // generate and register the participations
+            let participant = Self::env().caller();
+            for _ in 0..count {
+                // the next ticket id is used so that each generated ticket is different
+                let ticket_id = Raffle::get_nb_participations(self, raffle_id);
+                let entropy = (seed, participant, ticket_id).encode();
+                let numbers = RaffleConfig::generate_numbers(self, game_id, &entropy)?;
+                self.inner_participate(game_id, numbers)?;
+            }
+            // pay all tickets
+            self.pay_tickets(game_id, count as usize)?;
+            Ok(())
+        }
+
+        /// collect the payment of the tickets from the caller in the currency of the contract
+        fn pay_tickets(&mut self, game_id: GameId, nb_tickets: usize) -> Result<(), ContractError> {
+            let price = RaffleConfig::get_tickets_price(self, game_id, nb_tickets)?;
+            let participant = Self::env().caller();
+            RewardManager::collect_payment(self, participant, price)?;
+            Ok(())
+        }
+
+        fn inner_participate(
+            &mut self,
+            game_id: GameId,
+            numbers: Vec<Number>,
+        ) -> Result<(), ContractError> {
+            // check if the sales are not paused
+            PauseManager::ensure_not_paused(self, Capability::Sales)?;
             // check if the numbers are correct
-            RaffleConfig::check_numbers(self, &numbers)?;
+            RaffleConfig::check_numbers(self, game_id, &numbers)?;
+            // save the ticket in its canonical form (sorted numbers in unordered mode)
+            let numbers = RaffleConfig::canonicalize_numbers(self, game_id, numbers)?;
             // check if the user can participate (raffle is open)
-            Raffle::can_participate(self)?;
-            // save the participation with an event
+            Raffle::can_participate(self, game_id)?;
+            // check the limits of tickets per account and per raffle
             let participant = Self::env().caller();
-            let raffle_id = Raffle::get_current_raffle_id(self);
+            let config = RaffleConfig::ensure_config(self, game_id)?;
+            Raffle::ensure_participation_limits(self, game_id, participant, &config)?;
+            // save the participation
+            let ticket_id = Raffle::add_participation(self, game_id, participant, numbers.clone())?;
+            // mint the ticket, its metadata hold the raffle id and the numbers
+            let raffle_id = Raffle::get_current_raffle_id(self, game_id);
+            let token_id = get_ticket_token_id(raffle_id, ticket_id);
+            psp34::Internal::_mint_to(self, participant, token_id.clone())?;
+            // credit the prize pool with the price of the ticket, refunded to its owner if the raffle is cancelled
+            let price = RaffleConfig::get_tickets_price(self, game_id, 1)?;
+            RewardManager::add_to_prize_pool(self, raffle_id, ticket_id, price)?;
+            metadata::Internal::_set_attribute(
+                self,
+                token_id.clone(),
+                String::from("raffle_id"),
+                raffle_id.to_string(),
+            );
+            let numbers_attribute: Vec<String> = numbers.iter().map(|n| n.to_string()).collect();
+            metadata::Internal::_set_attribute(
+                self,
+                token_id,
+                String::from("numbers"),
+                numbers_attribute.join(","),
+            );
+            // emit the event
             self.env().emit_event(ParticipationRegistered {
                 raffle_id,
                 participant,
@@ -223,93 +527,280 @@ pub mod lotto_contract {
         }
 
         #[ink(message)]
-        pub fn participate_batch(
-            &mut self,
-            numbers: Vec<Vec<Number>>,
-        ) -> Result<(), ContractError> {
-            // check if the numbers are correct
-            for n in numbers {
-                self.participate(n)?;
+        #[openbrush::modifiers(access_control::only_role(CONFIG_MANAGER_ROLE))]
+        pub fn set_config(&mut self, game_id: GameId, config: Config) -> Result<(), RaffleError> {
+            // check the status, we can set the config only between two raffles
+            let status = Raffle::get_current_status(self, game_id);
+            if !matches!(
+                status,
+                Status::NotStarted | Status::Closed | Status::Cancelled
+            ) {
+                return Err(RaffleError::IncorrectStatus);
             }
 
+            // update the config
+            RaffleConfig::set_config(self, game_id, config)?;
+
             Ok(())
         }
 
+        /// schedule the config applied when the next raffle starts, the ongoing raffle keeps its config
         #[ink(message)]
-        #[openbrush::modifiers(access_control::only_role(LOTTO_MANAGER_ROLE))]
-        pub fn set_config(&mut self, config: Config) -> Result<(), RaffleError> {
-            // check the status, we can set the config only when the raffle is not started yet
-            let status = Raffle::get_current_status(self);
-            if status != Status::NotStarted {
-                return Err(RaffleError::IncorrectStatus);
-            }
+        #[openbrush::modifiers(access_control::only_role(CONFIG_MANAGER_ROLE))]
+        pub fn schedule_config(
+            &mut self,
+            game_id: GameId,
+            config: Config,
+        ) -> Result<(), RaffleError> {
+            RaffleConfig::schedule_config(self, game_id, config)?;
 
-            // update the config
-            RaffleConfig::set_config(self, config)?;
+            // emit the event
+            self.env().emit_event(ConfigScheduled { game_id });
 
             Ok(())
         }
 
         #[ink(message)]
-        #[openbrush::modifiers(access_control::only_role(LOTTO_MANAGER_ROLE))]
-        pub fn start_raffle(&mut self) -> Result<RaffleId, ContractError> {
-            let raffle_id = self.inner_start_raffle()?;
+        #[openbrush::modifiers(access_control::only_role(RAFFLE_OPERATOR_ROLE))]
+        pub fn start_raffle(&mut self, game_id: GameId) -> Result<RaffleId, ContractError> {
+            let raffle_id = self.inner_start_raffle(game_id)?;
             Ok(raffle_id)
         }
 
-        fn inner_start_raffle(&mut self) -> Result<RaffleId, ContractError> {
+        fn inner_start_raffle(&mut self, game_id: GameId) -> Result<RaffleId, ContractError> {
+            // apply the scheduled config before starting the raffle
+            let config_applied = RaffleConfig::apply_scheduled_config(self, game_id).is_some();
+
             // start new raffle
-            let raffle_id = Raffle::start_new_raffle(self)?;
+            let duration = RaffleConfig::get_config(self, game_id).and_then(|c| c.raffle_duration);
+            let raffle_id = Raffle::start_new_raffle(self, game_id, duration)?;
 
-            // emit the event
-            self.env().emit_event(RaffleStarted { raffle_id });
+            // keep the config used by this raffle
+            RaffleConfig::save_config_of(self, game_id, raffle_id);
+
+            // emit the events
+            if config_applied {
+                self.env().emit_event(ConfigApplied { game_id, raffle_id });
+            }
+            self.env().emit_event(RaffleStarted { game_id, raffle_id });
+
+            // request the seed used to generate the quick-pick tickets if the game uses them
+            if RaffleConfig::get_config_of(self, raffle_id).map_or(false, |c| c.quick_pick) {
+                self.push_request(LottoRequestMessage {
+                    game_id,
+                    raffle_id,
+                    request: Request::DrawQuickPickSeed,
+                })?;
+            }
 
             Ok(raffle_id)
         }
 
         #[ink(message)]
-        #[openbrush::modifiers(access_control::only_role(LOTTO_MANAGER_ROLE))]
-        pub fn complete_raffle(&mut self) -> Result<(), ContractError> {
+        pub fn complete_raffle(&mut self, game_id: GameId) -> Result<(), ContractError> {
+            // the raffle operator can complete the raffle at any time, anyone else only after the end time
+            let caller = Self::env().caller();
+            if !AccessControl::has_role(self, RAFFLE_OPERATOR_ROLE, Some(caller)) {
+                Raffle::ensure_raffle_ended(self, game_id)?;
+            }
+
             // stop the current raffle
-            Raffle::stop_current_raffle(self)?;
+            Raffle::stop_current_raffle(self, game_id)?;
 
             // emit the event
-            let raffle_id = Raffle::get_current_raffle_id(self);
+            let raffle_id = Raffle::get_current_raffle_id(self, game_id);
             self.env().emit_event(RaffleEnded { raffle_id });
 
             // request the draw numbers
-            let config = RaffleConfig::ensure_config(self)?;
-            let message = LottoRequestMessage {
+            let request = self.build_draw_numbers_request(raffle_id)?;
+            self.push_request(LottoRequestMessage {
+                game_id,
                 raffle_id,
-                request: Request::DrawNumbers(
+                request,
+            })?;
+
+            Ok(())
+        }
+
+        /// push again the pending request if the phat contract did not answer before the timeout
+        #[ink(message)]
+        #[openbrush::modifiers(access_control::only_role(RAFFLE_OPERATOR_ROLE))]
+        pub fn resend_request(&mut self, game_id: GameId) -> Result<(), ContractError> {
+            // check the timeout
+            Raffle::ensure_request_timed_out(self, game_id)?;
+
+            // build the same request based on the status
+            let raffle_id = Raffle::get_current_raffle_id(self, game_id);
+            let request = match Raffle::get_current_status(self, game_id) {
+                Status::Ongoing
+                    if RaffleConfig::get_config_of(self, raffle_id)
+                        .map_or(false, |c| c.quick_pick)
+                        && Raffle::get_quick_pick_seed(self, raffle_id).is_none() =>
+                {
+                    Request::DrawQuickPickSeed
+                }
+                Status::WaitingResults => self.build_draw_numbers_request(raffle_id)?,
+                Status::WaitingWinners => {
+                    let numbers =
+                        Raffle::get_results(self, raffle_id).ok_or(RaffleError::IncorrectStatus)?;
+                    self.build_check_winners_request(raffle_id, numbers)?
+                }
+                _ => return Err(RaffleError::IncorrectStatus.into()),
+            };
+            self.push_request(LottoRequestMessage {
+                game_id,
+                raffle_id,
+                request,
+            })?;
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[openbrush::modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        pub fn set_request_timeout(&mut self, timeout: Timestamp) -> Result<(), ContractError> {
+            Raffle::set_request_timeout(self, timeout);
+            Ok(())
+        }
+
+        fn build_draw_numbers_request(
+            &self,
+            raffle_id: RaffleId,
+        ) -> Result<Request, ContractError> {
+            let config = RaffleConfig::ensure_config_of(self, raffle_id)?;
+            Ok(Request::DrawNumbers(
+                config.nb_numbers,
+                config.min_number,
+                config.max_number,
+                config
+                    .bonus_pool
+                    .map(|b| (b.nb_numbers, b.min_number, b.max_number)),
+                config.draw_mode,
+            ))
+        }
+
+        fn build_check_winners_request(
+            &self,
+            raffle_id: RaffleId,
+            numbers: Vec<Number>,
+        ) -> Result<Request, ContractError> {
+            let config = RaffleConfig::ensure_config_of(self, raffle_id)?;
+            let request = if config.prize_tiers.is_empty()
+                && config.bonus_pool.is_none()
+                && config.draw_mode == DrawMode::Unordered
+            {
+                Request::CheckWinners(numbers)
+            } else {
+                let nb_matching_numbers = RaffleConfig::ensure_prize_tiers(self, raffle_id)?
+                    .iter()
+                    .map(|tier| (tier.nb_matching_numbers, tier.nb_matching_bonus_numbers))
+                    .collect();
+                Request::CheckTieredWinners(
+                    numbers,
                     config.nb_numbers,
-                    config.min_number,
-                    config.max_number,
-                ),
+                    nb_matching_numbers,
+                    config.draw_mode,
+                )
             };
+            Ok(request)
+        }
+
+        /// push the request in the queue and save when it has been queued
+        fn push_request(&mut self, message: LottoRequestMessage) -> Result<(), ContractError> {
             RollupAnchor::push_message(self, &message)?;
+            Raffle::set_request_queued(self, message.game_id);
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn cancel_raffle(&mut self, game_id: GameId) -> Result<(), ContractError> {
+            // only the raffle operator or the admin can cancel the raffle
+            let caller = Some(Self::env().caller());
+            if !AccessControl::has_role(self, RAFFLE_OPERATOR_ROLE, caller)
+                && !AccessControl::has_role(self, DEFAULT_ADMIN_ROLE, caller)
+            {
+                return Err(AccessControlError::MissingRole.into());
+            }
+
+            // cancel the current raffle
+            let raffle_id = Raffle::cancel_current_raffle(self, game_id)?;
 
+            // emit the event
+            self.env().emit_event(RaffleCancelled { raffle_id });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[openbrush::modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        pub fn set_cancellation_delay(&mut self, delay: Timestamp) -> Result<(), ContractError> {
+            Raffle::set_cancellation_delay(self, delay)?;
+            Ok(())
+        }
+
+        /// pause the capability in case of emergency
+        #[ink(message)]
+        #[openbrush::modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        pub fn pause(&mut self, capability: Capability) -> Result<(), ContractError> {
+            PauseManager::set_paused(self, capability, true)?;
+            self.env().emit_event(Paused { capability });
+            Ok(())
+        }
+
+        /// resume the capability
+        #[ink(message)]
+        #[openbrush::modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        pub fn unpause(&mut self, capability: Capability) -> Result<(), ContractError> {
+            PauseManager::set_paused(self, capability, false)?;
+            self.env().emit_event(Unpaused { capability });
+            Ok(())
+        }
+
+        /// refund the tickets of a cancelled raffle to the caller, who must be their current owner
+        #[ink(message)]
+        pub fn claim_refund(
+            &mut self,
+            raffle_id: RaffleId,
+            ticket_ids: Vec<TicketId>,
+        ) -> Result<(), ContractError> {
+            PauseManager::ensure_not_paused(self, Capability::Claims)?;
+            if !Raffle::is_cancelled(self, raffle_id) {
+                return Err(RaffleError::RaffleNotCancelled.into());
+            }
+            let owner = Self::env().caller();
+            for ticket_id in ticket_ids.iter() {
+                if PSP34::owner_of(self, get_ticket_token_id(raffle_id, *ticket_id)) != Some(owner)
+                {
+                    return Err(RaffleError::NotTicketOwner.into());
+                }
+            }
+            RewardManager::refund(self, raffle_id, &ticket_ids, owner)?;
             Ok(())
         }
 
         fn inner_set_results(
             &mut self,
+            game_id: GameId,
             raffle_id: RaffleId,
             config: Config,
             numbers: Vec<Number>,
         ) -> Result<(), ContractError> {
             // check if the config used to select the number is correct
-            RaffleConfig::ensure_same_config(self, &config)?;
+            RaffleConfig::ensure_same_config(self, raffle_id, &config)?;
 
-            // check if the numbers are correct
-            RaffleConfig::check_numbers(self, &numbers)?;
+            // check if the numbers are correct (based on the config used by the raffle)
+            RaffleConfig::ensure_config_of(self, raffle_id)?.check_numbers(&numbers)?;
 
             // set the result
-            Raffle::set_results(self, raffle_id, numbers.clone())?;
+            Raffle::set_results(self, game_id, raffle_id, numbers.clone())?;
 
-            // save in the kv store the last raffle id used for verification
+            // save in the kv store the last raffle id of the game used for verification
             const LAST_RAFFLE: u32 = ink::selector_id!("LAST_RAFFLE_FOR_VERIF");
-            RollupAnchor::set_value(self, &LAST_RAFFLE.encode(), Some(&raffle_id.encode()));
+            RollupAnchor::set_value(
+                self,
+                &(LAST_RAFFLE, game_id).encode(),
+                Some(&raffle_id.encode()),
+            );
 
             // emmit the event
             self.env().emit_event(ResultReceived {
@@ -318,26 +809,68 @@ pub mod lotto_contract {
             });
 
             // request to check the winners
-            let message = LottoRequestMessage {
+            let request = self.build_check_winners_request(raffle_id, numbers)?;
+            self.push_request(LottoRequestMessage {
+                game_id,
                 raffle_id,
-                request: Request::CheckWinners(numbers),
-            };
-            self.push_message(&message)?;
+                request,
+            })?;
 
             Ok(())
         }
 
         pub fn inner_set_winners(
             &mut self,
+            game_id: GameId,
             raffle_id: RaffleId,
             numbers: Vec<Number>,
-            winners: Vec<AccountId>,
+            tiered_winners: Vec<(u8, u8, Vec<AccountId>)>,
         ) -> Result<(), ContractError> {
             // check if the winners were selected based on the correct numbers
-            Raffle::ensure_same_results(self, raffle_id, &numbers)?;
+            let config = RaffleConfig::ensure_config_of(self, raffle_id)?;
+            Raffle::ensure_same_results(self, raffle_id, &config, &numbers)?;
+
+            // link the winners to the prize tiers
+            let prize_tiers = RaffleConfig::ensure_prize_tiers(self, raffle_id)?;
+            let mut tiers: Vec<(PrizeTier, Vec<AccountId>)> = Vec::new();
+            let mut winners = Vec::new();
+            for (nb_matching_numbers, nb_matching_bonus_numbers, tier_winners) in tiered_winners {
+                // each prize tier can be rewarded only once
+                if tiers.iter().any(|(t, _)| {
+                    t.nb_matching_numbers == nb_matching_numbers
+                        && t.nb_matching_bonus_numbers == nb_matching_bonus_numbers
+                }) {
+                    return Err(RaffleError::DuplicatedPrizeTier.into());
+                }
+                let tier = prize_tiers
+                    .iter()
+                    .find(|t| {
+                        t.nb_matching_numbers == nb_matching_numbers
+                            && t.nb_matching_bonus_numbers == nb_matching_bonus_numbers
+                    })
+                    .ok_or(RaffleError::IncorrectPrizeTier)?;
+                // check if the winners hold a ticket matching the numbers of this tier
+                let tickets =
+                    Raffle::ensure_winners(self, raffle_id, &config, tier, &tier_winners)?;
+                // each winning ticket gets a share, paid to its current owner
+                let owners: Vec<AccountId> = tickets
+                    .into_iter()
+                    .map(|(ticket_id, participant)| {
+                        PSP34::owner_of(self, get_ticket_token_id(raffle_id, ticket_id))
+                            .unwrap_or(participant)
+                    })
+                    .collect();
+                // the winners of the raffle are listed once, the rewards are shared per ticket
+                for owner in owners.iter() {
+                    if !winners.contains(owner) {
+                        winners.push(*owner);
+                    }
+                }
+                tiers.push((*tier, owners));
+            }
 
             // set the winners in the raffle
-            Raffle::set_winners(self, raffle_id, winners.clone())?;
+            Raffle::set_winners(self, game_id, raffle_id, winners.clone())?;
 
             // emmit the event
             self.env().emit_event(WinnersRevealed {
@@ -347,17 +880,19 @@ pub mod lotto_contract {
 
             // set the winners in the reward manager
             if !winners.is_empty() {
-                RewardManager::add_winners(self, winners)?;
+                RewardManager::add_winners(self, game_id, raffle_id, tiers, config.claim_duration)?;
             } else {
+                // roll the prize pool over to the next raffle if there is no winner
+                RewardManager::roll_over_prize_pool(self, game_id, raffle_id)?;
                 // start automatically the new raffle if there is no winner
-                self.inner_start_raffle()?;
+                self.inner_start_raffle(game_id)?;
             }
 
             Ok(())
         }
 
         #[ink(message)]
-        #[modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        #[modifiers(only_role(ATTESTOR_ADMIN_ROLE))]
         pub fn register_attestor(
             &mut self,
             account_id: AccountId,
@@ -366,14 +901,83 @@ pub mod lotto_contract {
             Ok(())
         }
 
+        /// set the number of attestors who must send the same results and winners before they are applied
+        #[ink(message)]
+        #[modifiers(only_role(ATTESTOR_ADMIN_ROLE))]
+        pub fn set_attestor_threshold(&mut self, threshold: u32) -> Result<(), ContractError> {
+            AttestorQuorum::set_attestor_threshold(self, threshold)?;
+            Ok(())
+        }
+
+        /// record the vote of the attestor for the response and return true if the quorum is reached.
+        /// Without quorum, the request is pushed again to be answered by another attestor
+        fn record_vote(
+            &mut self,
+            attestor: AccountId,
+            request: &LottoRequestMessage,
+            response: &[u8],
+        ) -> Result<bool, ContractError> {
+            let threshold = AttestorQuorum::get_attestor_threshold(self);
+            if threshold <= 1 {
+                return Ok(true);
+            }
+
+            if !AccessControl::has_role(self, ATTESTOR_ROLE, Some(attestor)) {
+                return Err(AccessControlError::MissingRole.into());
+            }
+
+            // the attestors must send identical responses to the same request
+            let mut request_hash = [0u8; 32];
+            ink::env::hash_encoded::<Blake2x256, _>(request, &mut request_hash);
+            let mut response_hash = [0u8; 32];
+            ink::env::hash_bytes::<Blake2x256>(response, &mut response_hash);
+            let raffle_id = request.raffle_id;
+            // a second vote of the attestor for the request is not counted
+            if let Some(nb_votes) =
+                AttestorQuorum::add_vote(self, raffle_id, request_hash, attestor, response_hash)
+            {
+                // emit the event
+                self.env().emit_event(ResultVoteRecorded {
+                    raffle_id,
+                    attestor,
+                    nb_votes,
+                });
+
+                if nb_votes >= threshold {
+                    // the response is applied, the votes are not needed anymore
+                    AttestorQuorum::clear_votes(self, raffle_id, request_hash);
+                    return Ok(true);
+                }
+            }
+
+            // the quorum is not reached: the request (popped by the attestor) is pushed again
+            self.push_request(request.clone())?;
+            Ok(false)
+        }
+
         #[ink(message)]
         pub fn get_attestor_role(&self) -> RoleType {
             ATTESTOR_ROLE
         }
 
         #[ink(message)]
-        pub fn get_manager_role(&self) -> RoleType {
-            LOTTO_MANAGER_ROLE
+        pub fn get_attestor_admin_role(&self) -> RoleType {
+            ATTESTOR_ADMIN_ROLE
+        }
+
+        #[ink(message)]
+        pub fn get_config_manager_role(&self) -> RoleType {
+            CONFIG_MANAGER_ROLE
+        }
+
+        #[ink(message)]
+        pub fn get_raffle_operator_role(&self) -> RoleType {
+            RAFFLE_OPERATOR_ROLE
+        }
+
+        #[ink(message)]
+        pub fn get_treasurer_role(&self) -> RoleType {
+            TREASURER_ROLE
         }
 
         #[ink(message)]
@@ -382,49 +986,162 @@ pub mod lotto_contract {
             self.env().terminate_contract(self.env().caller());
         }
 
+        /// claim the pending rewards of the signer of the meta transaction (the signer is the beneficiary).
+        /// The data of the request are `META_TX_CLAIM_TAG` followed by the parameter of the claim:
+        /// the raffle id (None to claim all rewards)
         #[ink(message)]
-        #[openbrush::modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        pub fn meta_tx_claim(
+            &mut self,
+            request: ForwardRequest,
+            signature: [u8; 65],
+        ) -> Result<(), ContractError> {
+            // check the signature and the nonce
+            MetaTransaction::use_meta_tx(self, &request, &signature)?;
+            // the data must be tagged for the claim and must not contain trailing bytes
+            let (tag, raffle_id): (u32, Option<RaffleId>) =
+                scale::DecodeAll::decode_all(&mut request.data.as_slice())
+                    .map_err(|_| RollupAnchorError::FailedToDecode)?;
+            if tag != META_TX_CLAIM_TAG {
+                return Err(RollupAnchorError::FailedToDecode.into());
+            }
+            RewardManager::inner_claim(self, request.from, request.from, raffle_id)?;
+            Ok(())
+        }
+
+        /// move the rewards of the raffle not claimed before the deadline into the prize pool
+        /// of the next raffle with winners of the same game
+        #[ink(message)]
+        #[openbrush::modifiers(access_control::only_role(RAFFLE_OPERATOR_ROLE))]
+        pub fn sweep_expired_rewards(&mut self, raffle_id: RaffleId) -> Result<(), ContractError> {
+            let game_id =
+                Raffle::get_game_id(self, raffle_id).ok_or(RaffleError::IncorrectRaffle)?;
+            RewardManager::sweep_expired_rewards(self, game_id, raffle_id)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[openbrush::modifiers(only_role(TREASURER_ROLE))]
+        pub fn set_fee(&mut self, fee_rate: u16, treasury: AccountId) -> Result<(), ContractError> {
+            RewardManager::set_fee(self, fee_rate, treasury)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[openbrush::modifiers(only_role(TREASURER_ROLE))]
         pub fn withdraw(&mut self, value: Balance) -> Result<(), ContractError> {
+            // the funds owed to the winners, the treasury and the prize pools cannot be withdrawn
+            if value > RewardManager::get_available_balance(self) {
+                return Err(RaffleError::InsufficientBalance.into());
+            }
             let caller = Self::env().caller();
-            self.env()
-                .transfer(caller, value)
-                .map_err(|_| ContractError::TransferError)?;
+            RewardManager::transfer_to(self, caller, value)?;
+            Ok(())
+        }
+
+        /// set the currency (native or PSP22 token) used for the tickets and the rewards
+        #[ink(message)]
+        #[openbrush::modifiers(only_role(DEFAULT_ADMIN_ROLE))]
+        pub fn set_currency(&mut self, currency: Currency) -> Result<(), ContractError> {
+            RewardManager::set_currency(self, currency)?;
             Ok(())
         }
     }
 
-    impl rollup_anchor::MessageHandler for Contract {
-        fn on_message_received(&mut self, action: Vec<u8>) -> Result<(), RollupAnchorError> {
+    impl Contract {
+        /// handle the response sent by the attestor (the caller or the signer of the meta transaction)
+        fn handle_message(
+            &mut self,
+            action: Vec<u8>,
+            attestor: AccountId,
+        ) -> Result<(), RollupAnchorError> {
             // parse the response
             let message: LottoResponseMessage = scale::Decode::decode(&mut &action[..])
                 .or(Err(RollupAnchorError::FailedToDecode))?;
 
+            // the messages are not handled while the rollup is paused, they can be sent again later
+            PauseManager::ensure_not_paused(self, Capability::Rollup)
+                .map_err(ContractError::from)?;
+
+            let game_id = message.request.game_id;
             let raffle_id = message.request.raffle_id;
 
-            match message.response {
+            // ignore the late responses for a cancelled raffle
+            if Raffle::is_cancelled(self, raffle_id) {
+                return Ok(());
+            }
+
+            // in the M-of-N mode, the results and the winners are applied once enough attestors sent the same response
+            if !matches!(message.response, Response::QuickPickSeed(_))
+                && !self.record_vote(attestor, &message.request, &action)?
+            {
+                return Ok(());
+            }
+
+            let result = match message.response {
                 Response::Numbers(numbers) => {
                     let config = match message.request.request {
-                        Request::DrawNumbers(nb_numbers, min_number, max_number) => Config {
+                        Request::DrawNumbers(
                             nb_numbers,
                             min_number,
                             max_number,
+                            bonus,
+                            draw_mode,
+                        ) => Config {
+                            nb_numbers,
+                            min_number,
+                            max_number,
+                            bonus_pool: bonus.map(|(nb_numbers, min_number, max_number)| {
+                                BonusPool {
+                                    nb_numbers,
+                                    min_number,
+                                    max_number,
+                                }
+                            }),
+                            draw_mode,
+                            // only the draw parameters are checked
+                            ..Default::default()
                         },
                         _ => return Err(RollupAnchorError::UnsupportedAction),
                     };
-                    self.inner_set_results(raffle_id, config, numbers)
-                        .or(Err(RollupAnchorError::UnsupportedAction))?
+                    self.inner_set_results(game_id, raffle_id, config, numbers)
                 }
                 Response::Winners(winners) => {
                     let numbers = match message.request.request {
                         Request::CheckWinners(numbers) => numbers,
                         _ => return Err(RollupAnchorError::UnsupportedAction),
                     };
-                    self.inner_set_winners(raffle_id, numbers, winners)
-                        .or(Err(RollupAnchorError::UnsupportedAction))?
+                    // the winners match all numbers
+                    let tiered_winners = vec![(numbers.len() as u8, 0, winners)];
+                    self.inner_set_winners(game_id, raffle_id, numbers, tiered_winners)
+                }
+                Response::TieredWinners(tiered_winners) => {
+                    let numbers = match message.request.request {
+                        Request::CheckTieredWinners(numbers, _, _, _) => numbers,
+                        _ => return Err(RollupAnchorError::UnsupportedAction),
+                    };
+                    self.inner_set_winners(game_id, raffle_id, numbers, tiered_winners)
                 }
+                Response::QuickPickSeed(seed) => {
+                    if message.request.request != Request::DrawQuickPickSeed {
+                        return Err(RollupAnchorError::UnsupportedAction);
+                    }
+                    Raffle::set_quick_pick_seed(self, raffle_id, seed).map_err(Into::into)
+                }
+            };
+
+            match result {
+                // the request may have been pushed several times: only the first answer is applied
+                Err(ContractError::RaffleError(RaffleError::ExistingResults))
+                | Err(ContractError::RaffleError(RaffleError::ExistingWinners))
+                | Err(ContractError::RaffleError(RaffleError::ExistingQuickPickSeed)) => Ok(()),
+                r => r.or(Err(RollupAnchorError::UnsupportedAction)),
             }
+        }
+    }
 
-            Ok(())
+    impl rollup_anchor::MessageHandler for Contract {
+        fn on_message_received(&mut self, action: Vec<u8>) -> Result<(), RollupAnchorError> {
+            self.handle_message(action, Self::env().caller())
         }
     }
 
@@ -459,12 +1176,78 @@ pub mod lotto_contract {
     }
 
     impl reward::Internal for Contract {
-        fn emit_pending_reward_event(&self, account: AccountId, amount: Balance) {
-            self.env().emit_event(PendingReward { account, amount });
+        fn ensure_claims_allowed(&self) -> Result<(), RaffleError> {
+            PauseManager::ensure_not_paused(self, Capability::Claims)
+        }
+
+        fn emit_pending_reward_event(
+            &self,
+            account: AccountId,
+            raffle_id: RaffleId,
+            amount: Balance,
+        ) {
+            self.env().emit_event(PendingReward {
+                account,
+                raffle_id,
+                amount,
+            });
+        }
+
+        fn emit_rewards_claimed_event(
+            &self,
+            account: AccountId,
+            raffle_id: RaffleId,
+            amount: Balance,
+        ) {
+            self.env().emit_event(RewardsClaimed {
+                account,
+                raffle_id,
+                amount,
+            });
+        }
+
+        fn emit_jackpot_rolled_over_event(&self, raffle_id: RaffleId, amount: Balance) {
+            self.env()
+                .emit_event(JackpotRolledOver { raffle_id, amount });
         }
 
-        fn emit_rewards_claimed_event(&self, account: AccountId, amount: Balance) {
-            self.env().emit_event(RewardsClaimed { account, amount });
+        fn emit_fees_withdrawn_event(&self, treasury: AccountId, amount: Balance) {
+            self.env().emit_event(FeesWithdrawn { treasury, amount });
+        }
+
+        fn emit_refund_claimed_event(
+            &self,
+            raffle_id: RaffleId,
+            account: AccountId,
+            amount: Balance,
+        ) {
+            self.env().emit_event(RefundClaimed {
+                raffle_id,
+                account,
+                amount,
+            });
+        }
+
+        fn emit_expired_rewards_swept_event(&self, raffle_id: RaffleId, amount: Balance) {
+            self.env()
+                .emit_event(ExpiredRewardsSwept { raffle_id, amount });
+        }
+
+        fn emit_prize_tier_rewarded_event(
+            &self,
+            raffle_id: RaffleId,
+            nb_matching_numbers: u8,
+            nb_matching_bonus_numbers: u8,
+            winners: Vec<AccountId>,
+            reward: Balance,
+        ) {
+            self.env().emit_event(PrizeTierRewarded {
+                raffle_id,
+                nb_matching_numbers,
+                nb_matching_bonus_numbers,
+                winners,
+                reward,
+            });
         }
     }
 }
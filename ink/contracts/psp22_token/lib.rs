@@ -0,0 +1,26 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// PSP22 token used in the e2e tests as currency of the lotto contract
+#[openbrush::implementation(PSP22)]
+#[openbrush::contract]
+pub mod psp22_token {
+    use openbrush::traits::Storage;
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Contract {
+        #[storage_field]
+        psp22: psp22::Data,
+    }
+
+    impl Contract {
+        /// mint the total supply for the caller
+        #[ink(constructor)]
+        pub fn new(total_supply: Balance) -> Self {
+            let mut instance = Self::default();
+            psp22::Internal::_mint_to(&mut instance, Self::env().caller(), total_supply)
+                .expect("Should mint the total supply");
+            instance
+        }
+    }
+}
@@ -6,31 +6,52 @@ mod e2e_tests {
     use ink_e2e::subxt::tx::Signer;
     use ink_e2e::{build_message, PolkadotConfig};
     use openbrush::contracts::access_control::accesscontrol_external::AccessControl;
+    use openbrush::contracts::psp22::psp22_external::PSP22;
+    use openbrush::contracts::psp34::extensions::metadata::psp34metadata_external::PSP34Metadata;
+    use openbrush::contracts::psp34::psp34_external::PSP34;
     use openbrush::traits::AccountId;
     use openbrush::traits::Balance;
+    use openbrush::traits::Timestamp;
     use scale::Decode;
     use scale::Encode;
 
-    use lotto::traits::config::Config;
+    use lotto::traits::config::raffleconfig_external::RaffleConfig;
+    use lotto::traits::config::{BonusPool, Config, DrawMode, PrizeTier};
+    use lotto::traits::pause::pausemanager_external::PauseManager;
+    use lotto::traits::pause::Capability;
+    use lotto::traits::quorum::attestorquorum_external::AttestorQuorum;
     use lotto::traits::raffle::raffle_external::Raffle;
     use lotto::traits::reward::rewardmanager_external::RewardManager;
+    use lotto::traits::reward::Currency;
+    use lotto::traits::GameId;
     use lotto::traits::Number;
     use lotto::traits::RaffleId;
+    use lotto::traits::TicketId;
 
     use lotto_contract::{lotto_contract, *};
+    use psp22_token::psp22_token;
 
     use phat_rollup_anchor_ink::traits::meta_transaction::metatransaction_external::MetaTransaction;
     use phat_rollup_anchor_ink::traits::rollup_anchor::rollupanchor_external::RollupAnchor;
 
-    use lotto::traits::raffle::Status;
+    use lotto::traits::raffle::{Participation, Status};
     use phat_rollup_anchor_ink::traits::rollup_anchor::*;
 
     type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
 
+    const GAME_ID: GameId = 1;
+
     async fn alice_instantiates_contract(
         client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
     ) -> AccountId {
-        let lotto_constructor = lotto_contract::ContractRef::new();
+        // alice holds all roles
+        let alice_address = ink::primitives::AccountId::from(ink_e2e::alice().public_key().0);
+        let lotto_constructor = lotto_contract::ContractRef::new(
+            alice_address,
+            alice_address,
+            alice_address,
+            alice_address,
+        );
         let lotto_contract_id = client
             .instantiate(
                 "lotto_contract",
@@ -46,17 +67,29 @@ mod e2e_tests {
         lotto_contract_id
     }
 
+    fn default_config() -> Config {
+        Config {
+            nb_numbers: 4,
+            min_number: 1,
+            max_number: 50,
+            ..Default::default()
+        }
+    }
+
     async fn alice_configures_contract(
         client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
         contract_id: &AccountId,
     ) {
-        let config = Config {
-            nb_numbers: 4,
-            min_number: 1,
-            max_number: 50,
-        };
+        alice_sets_config(client, contract_id, default_config()).await;
+    }
+
+    async fn alice_sets_config(
+        client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
+        contract_id: &AccountId,
+        config: Config,
+    ) {
         let set_config = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.set_config(config));
+            .call(|contract| contract.set_config(GAME_ID, config));
         client
             .call(&ink_e2e::alice(), set_config, 0, None)
             .await
@@ -82,7 +115,7 @@ mod e2e_tests {
         contract_id: &AccountId,
     ) -> RaffleId {
         let start_raffle = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.start_raffle());
+            .call(|contract| contract.start_raffle(GAME_ID));
         client
             .call(&ink_e2e::alice(), start_raffle, 0, None)
             .await
@@ -96,7 +129,7 @@ mod e2e_tests {
         contract_id: &AccountId,
     ) {
         let stop_raffle = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.complete_raffle());
+            .call(|contract| contract.complete_raffle(GAME_ID));
         client
             .call(&ink_e2e::alice(), stop_raffle, 0, None)
             .await
@@ -110,8 +143,9 @@ mod e2e_tests {
         numbers: Vec<Number>,
     ) {
         let request = LottoRequestMessage {
+            game_id: GAME_ID,
             raffle_id,
-            request: Request::DrawNumbers(4, 1, 50),
+            request: Request::DrawNumbers(4, 1, 50, None, DrawMode::Unordered),
         };
 
         let payload = LottoResponseMessage {
@@ -139,6 +173,7 @@ mod e2e_tests {
         winners: Vec<AccountId>,
     ) {
         let request = LottoRequestMessage {
+            game_id: GAME_ID,
             raffle_id,
             request: Request::CheckWinners(numbers.clone()),
         };
@@ -160,6 +195,42 @@ mod e2e_tests {
         assert!(result.contains_event("Contracts", "ContractEmitted"));
     }
 
+    async fn bob_sends_tiered_winners(
+        client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
+        contract_id: &AccountId,
+        raffle_id: RaffleId,
+        numbers: Vec<Number>,
+        nb_numbers: u8,
+        nb_matching_numbers: Vec<(u8, u8)>,
+        tiered_winners: Vec<(u8, u8, Vec<AccountId>)>,
+    ) {
+        let request = LottoRequestMessage {
+            game_id: GAME_ID,
+            raffle_id,
+            request: Request::CheckTieredWinners(
+                numbers.clone(),
+                nb_numbers,
+                nb_matching_numbers,
+                DrawMode::Unordered,
+            ),
+        };
+
+        let payload = LottoResponseMessage {
+            request,
+            response: Response::TieredWinners(tiered_winners.clone()),
+        };
+
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+
+        let result = client
+            .call(&ink_e2e::bob(), rollup_cond_eq, 0, None)
+            .await
+            .expect("send tiered winners failed");
+        assert!(result.contains_event("Contracts", "ContractEmitted"));
+    }
+
     async fn participates(
         client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
         contract_id: &AccountId,
@@ -167,20 +238,98 @@ mod e2e_tests {
         numbers: Vec<Number>,
     ) {
         let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.participate(numbers.clone()));
+            .call(|contract| contract.participate(GAME_ID, numbers.clone()));
         client
             .call(signer, participate, 0, None)
             .await
             .expect("Participate failed");
     }
 
+    async fn get_prize_pool(
+        client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
+        contract_id: &AccountId,
+        raffle_id: RaffleId,
+    ) -> Balance {
+        let get_prize_pool = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_prize_pool(raffle_id));
+
+        let result = client
+            .call_dry_run(&ink_e2e::alice(), &get_prize_pool, 0, None)
+            .await;
+
+        result.return_value()
+    }
+
+    async fn get_participations_of(
+        client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
+        contract_id: &AccountId,
+        account_id: &AccountId,
+        raffle_id: RaffleId,
+    ) -> Vec<Participation> {
+        let get_participations_of =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.get_participations_of(*account_id, raffle_id));
+
+        let result = client
+            .call_dry_run(&ink_e2e::alice(), &get_participations_of, 0, None)
+            .await;
+
+        result.return_value()
+    }
+
+    async fn get_participations(
+        client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
+        contract_id: &AccountId,
+        raffle_id: RaffleId,
+        from: TicketId,
+        limit: u32,
+    ) -> Vec<Participation> {
+        let get_participations = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_participations(raffle_id, from, limit));
+
+        let result = client
+            .call_dry_run(&ink_e2e::alice(), &get_participations, 0, None)
+            .await;
+
+        result.return_value()
+    }
+
+    async fn get_end_time(
+        client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
+        contract_id: &AccountId,
+        raffle_id: RaffleId,
+    ) -> Option<Timestamp> {
+        let get_end_time = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_end_time(raffle_id));
+
+        let result = client
+            .call_dry_run(&ink_e2e::alice(), &get_end_time, 0, None)
+            .await;
+
+        result.return_value()
+    }
+
+    async fn get_rollover_amount(
+        client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
+        contract_id: &AccountId,
+    ) -> Balance {
+        let get_rollover_amount = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_rollover_amount(GAME_ID));
+
+        let result = client
+            .call_dry_run(&ink_e2e::alice(), &get_rollover_amount, 0, None)
+            .await;
+
+        result.return_value()
+    }
+
     async fn get_current_raffle_id(
         client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
         contract_id: &AccountId,
     ) -> RaffleId {
         let get_current_raffle_id =
             build_message::<lotto_contract::ContractRef>(contract_id.clone())
-                .call(|contract| contract.get_current_raffle_id());
+                .call(|contract| contract.get_current_raffle_id(GAME_ID));
 
         let raffle_id = client
             .call_dry_run(&ink_e2e::alice(), &get_current_raffle_id, 0, None)
@@ -198,7 +347,7 @@ mod e2e_tests {
         const LAST_RAFFLE_FOR_VERIF: u32 = ink::selector_id!("LAST_RAFFLE_FOR_VERIF");
 
         let get_value = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.get_value(LAST_RAFFLE_FOR_VERIF.encode()));
+            .call(|contract| contract.get_value((LAST_RAFFLE_FOR_VERIF, GAME_ID).encode()));
 
         let raffle_id = client
             .call_dry_run(&ink_e2e::alice(), &get_value, 0, None)
@@ -213,12 +362,31 @@ mod e2e_tests {
         }
     }
 
+    async fn get_queue_tail(
+        client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
+        contract_id: &AccountId,
+    ) -> u32 {
+        // the tail of the queue of requests is kept in the kv store of the rollup anchor
+        let get_value = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_value(b"q/_tail".to_vec()));
+
+        let tail = client
+            .call_dry_run(&ink_e2e::alice(), &get_value, 0, None)
+            .await
+            .return_value();
+
+        match tail {
+            Some(t) => u32::decode(&mut t.as_slice()).expect("Cannot decode the queue tail"),
+            None => 0,
+        }
+    }
+
     async fn get_current_status(
         client: &mut ink_e2e::Client<PolkadotConfig, DefaultEnvironment>,
         contract_id: &AccountId,
     ) -> Status {
         let get_current_status = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.get_current_status());
+            .call(|contract| contract.get_current_status(GAME_ID));
 
         let result = client
             .call_dry_run(&ink_e2e::alice(), &get_current_status, 0, None)
@@ -301,7 +469,7 @@ mod e2e_tests {
 
         // fund the contract
         let fund_contract = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.fund());
+            .call(|contract| contract.fund(GAME_ID, value));
         client
             .call(&ink_e2e::alice(), fund_contract, value, None)
             .await
@@ -338,7 +506,7 @@ mod e2e_tests {
 
         // claim the rewards
         let claim_rewards = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.claim());
+            .call(|contract| contract.claim(None));
 
         client
             .call(signer, claim_rewards, 0, None)
@@ -445,10 +613,46 @@ mod e2e_tests {
             &mut client,
             &contract_id,
             &ink_e2e::charlie(),
-            vec![12, 4, 6, 4],
+            vec![12, 4, 6, 3],
         )
         .await;
 
+        // check the participations saved in the contract
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
+        assert_eq!(
+            4,
+            get_participations_of(&mut client, &contract_id, &dave_address, raffle_id)
+                .await
+                .len()
+        );
+        let participations = get_participations(&mut client, &contract_id, raffle_id, 3, 2).await;
+        assert_eq!(
+            vec![
+                Participation {
+                    participant: dave_address,
+                    numbers: vec![1, 4, 15, 44],
+                },
+                Participation {
+                    participant: charlie_address,
+                    numbers: vec![2, 3, 8, 50],
+                },
+            ],
+            participations
+        );
+        assert_eq!(
+            7,
+            get_participations(&mut client, &contract_id, raffle_id, 0, 100)
+                .await
+                .len()
+        );
+
+        // charlie participates with the same number twice => it must fail
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, vec![5, 5, 8, 2]));
+        let result = client.call(&ink_e2e::charlie(), participate, 0, None).await;
+        assert!(result.is_err(), "the numbers must be distinct");
+
         // stop the raffle
         alice_stops_raffle(&mut client, &contract_id).await;
         assert_eq!(
@@ -475,7 +679,6 @@ mod e2e_tests {
         );
 
         // send the winners (dave wins 100)
-        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
         bob_sends_winners(
             &mut client,
             &contract_id,
@@ -561,6 +764,13 @@ mod e2e_tests {
             get_current_raffle_id(&mut client, &contract_id).await
         );
 
+        // the jackpot is rolled over to the next raffle
+        assert_eq!(100, get_rollover_amount(&mut client, &contract_id).await);
+        assert_eq!(
+            0,
+            get_prize_pool(&mut client, &contract_id, raffle_id - 1).await
+        );
+
         // fund more the contract
         fund(&mut client, &contract_id, 100).await;
 
@@ -585,11 +795,10 @@ mod e2e_tests {
         alice_stops_raffle(&mut client, &contract_id).await;
 
         // send the results
-        let results: Vec<Number> = vec![8, 10, 4, 2];
+        let results: Vec<Number> = vec![2, 8, 40, 5];
         bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
 
         // send the winners => two winners (50 each)
-        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
         bob_sends_winners(
             &mut client,
             &contract_id,
@@ -621,7 +830,7 @@ mod e2e_tests {
 
         //check the results and winners for raffle 3
         assert_eq!(
-            Some(vec![8, 10, 4, 2]),
+            Some(vec![2, 8, 40, 5]),
             get_results(&mut client, &contract_id, 3).await
         );
         assert_eq!(
@@ -629,6 +838,10 @@ mod e2e_tests {
             get_winners(&mut client, &contract_id, 3).await
         );
 
+        // the whole jackpot has been distributed
+        assert_eq!(0, get_rollover_amount(&mut client, &contract_id).await);
+        assert_eq!(200, get_prize_pool(&mut client, &contract_id, 3).await);
+
         //check the total pending rewards
         assert_eq!(
             300,
@@ -656,117 +869,2233 @@ mod e2e_tests {
     }
 
     #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
-    async fn test_bad_attestor(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+    async fn test_paid_tickets(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
         // given
         let contract_id = alice_instantiates_contract(&mut client).await;
 
-        // bob is not granted as attestor => it should not be able to send a message
-        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.rollup_cond_eq(vec![], vec![], vec![]));
-        let result = client.call(&ink_e2e::bob(), rollup_cond_eq, 0, None).await;
-        assert!(
-            result.is_err(),
-            "only attestor should be able to send messages"
+        // configure the raffle with a ticket price
+        let config = Config {
+            ticket_price: 10,
+            ..default_config()
+        };
+        alice_sets_config(&mut client, &contract_id, config).await;
+
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        assert_eq!(
+            0,
+            get_prize_pool(&mut client, &contract_id, raffle_id).await
         );
 
-        // bob is granted as attestor
-        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        // dave participates without paying => it must fail
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, vec![5, 40, 8, 2]));
+        let result = client.call(&ink_e2e::dave(), participate, 0, None).await;
+        assert!(result.is_err(), "the ticket must be paid");
 
-        // then bob is able to send a message
-        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.rollup_cond_eq(vec![], vec![], vec![]));
+        // dave participates and pays the ticket
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, vec![5, 40, 8, 2]));
+        client
+            .call(&ink_e2e::dave(), participate, 10, None)
+            .await
+            .expect("Participate failed");
+        assert_eq!(
+            10,
+            get_prize_pool(&mut client, &contract_id, raffle_id).await
+        );
+
+        // charlie participates with two tickets but pays only one => it must fail
+        let tickets = vec![vec![5, 40, 8, 2], vec![3, 6, 7, 5]];
+        let participate_batch = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate_batch(GAME_ID, tickets.clone()));
         let result = client
-            .call(&ink_e2e::bob(), rollup_cond_eq, 0, None)
+            .call(&ink_e2e::charlie(), participate_batch, 10, None)
+            .await;
+        assert!(result.is_err(), "all tickets must be paid");
+
+        // charlie participates and pays the two tickets
+        let participate_batch = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate_batch(GAME_ID, tickets.clone()));
+        client
+            .call(&ink_e2e::charlie(), participate_batch, 20, None)
             .await
-            .expect("rollup cond eq failed");
-        // no event
-        assert!(!result.contains_event("Contracts", "ContractEmitted"));
+            .expect("Participate batch failed");
+        assert_eq!(
+            30,
+            get_prize_pool(&mut client, &contract_id, raffle_id).await
+        );
 
         Ok(())
     }
 
     #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
-    async fn test_bad_messages(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+    async fn test_participation_limits(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
         // given
         let contract_id = alice_instantiates_contract(&mut client).await;
 
-        // bob is granted as attestor
-        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        // configure the raffle with 2 tickets per account and 3 tickets per raffle
+        let config = Config {
+            max_tickets_per_account: Some(2),
+            max_tickets_per_raffle: Some(3),
+            ..default_config()
+        };
+        alice_sets_config(&mut client, &contract_id, config).await;
 
-        let actions = vec![HandleActionInput::Reply(58u128.encode())];
-        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
-        let result = client.call(&ink_e2e::bob(), rollup_cond_eq, 0, None).await;
-        assert!(
-            result.is_err(),
-            "we should not be able to proceed bad messages"
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+
+        // dave participates with three tickets => it must fail
+        let tickets = vec![vec![5, 40, 8, 2], vec![3, 6, 7, 5], vec![1, 2, 3, 4]];
+        let participate_batch = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate_batch(GAME_ID, tickets.clone()));
+        let result = client
+            .call(&ink_e2e::dave(), participate_batch, 0, None)
+            .await;
+        assert!(result.is_err(), "too many tickets for this account");
+
+        // dave participates with two tickets
+        let tickets = vec![vec![5, 40, 8, 2], vec![3, 6, 7, 5]];
+        let participate_batch = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate_batch(GAME_ID, tickets.clone()));
+        client
+            .call(&ink_e2e::dave(), participate_batch, 0, None)
+            .await
+            .expect("Participate batch failed");
+
+        // dave cannot buy another ticket
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, vec![1, 2, 3, 4]));
+        let result = client.call(&ink_e2e::dave(), participate, 0, None).await;
+        assert!(result.is_err(), "too many tickets for this account");
+
+        // charlie buys the last ticket of the raffle
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::charlie(),
+            vec![1, 2, 3, 4],
+        )
+        .await;
+
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, vec![5, 6, 7, 8]));
+        let result = client.call(&ink_e2e::charlie(), participate, 0, None).await;
+        assert!(result.is_err(), "too many tickets for this raffle");
+
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        assert_eq!(
+            2,
+            get_participations_of(&mut client, &contract_id, &dave_address, raffle_id)
+                .await
+                .len()
+        );
+        assert_eq!(
+            3,
+            get_participations(&mut client, &contract_id, raffle_id, 0, 100)
+                .await
+                .len()
         );
 
         Ok(())
     }
 
-    ///
-    /// Test the meta transactions
-    /// Alice is the owner
-    /// Bob is the attestor
-    /// Charlie is the sender (ie the payer)
-    ///
     #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
-    async fn test_meta_tx_rollup_cond_eq(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+    async fn test_currency(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
         let contract_id = alice_instantiates_contract(&mut client).await;
+        let token_address = ink::primitives::AccountId::from([1; 32]);
 
-        // Bob is the attestor
-        // use the ecsda account because we are not able to verify the sr25519 signature
-        let from = ink::primitives::AccountId::from(
-            Signer::<PolkadotConfig>::account_id(&subxt_signer::ecdsa::dev::bob()).0,
-        );
+        // charlie is not admin => charlie cannot set the currency
+        let set_currency = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_currency(Currency::PSP22(token_address)));
+        let result = client
+            .call(&ink_e2e::charlie(), set_currency, 0, None)
+            .await;
+        assert!(result.is_err(), "only the admin can set the currency");
 
-        // add the role => it should be succeed
-        let grant_role = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.grant_role(ATTESTOR_ROLE, Some(from)));
+        // alice sets a PSP22 token as currency
+        let set_currency = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_currency(Currency::PSP22(token_address)));
         client
-            .call(&ink_e2e::alice(), grant_role, 0, None)
+            .call(&ink_e2e::alice(), set_currency, 0, None)
             .await
-            .expect("grant the attestor failed");
+            .expect("set currency failed");
 
-        // prepare the meta transaction
-        let data = RollupCondEqMethodParams::encode(&(vec![], vec![], vec![]));
-        let prepare_meta_tx = build_message::<lotto_contract::ContractRef>(contract_id.clone())
-            .call(|contract| contract.prepare(from, data.clone()));
-        let result = client
-            .call(&ink_e2e::bob(), prepare_meta_tx, 0, None)
+        let get_currency = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_currency());
+        let currency = client
+            .call_dry_run(&ink_e2e::alice(), &get_currency, 0, None)
             .await
-            .expect("We should be able to prepare the meta tx");
+            .return_value();
+        assert_eq!(Currency::PSP22(token_address), currency);
 
-        let (request, _hash) = result
-            .return_value()
-            .expect("Expected value when preparing meta tx");
+        // alice goes back to the native currency and funds the contract
+        let set_currency = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_currency(Currency::Native));
+        client
+            .call(&ink_e2e::alice(), set_currency, 0, None)
+            .await
+            .expect("set currency failed");
+        fund(&mut client, &contract_id, 100).await;
 
-        assert_eq!(0, request.nonce);
-        assert_eq!(from, request.from);
-        assert_eq!(contract_id, request.to);
-        assert_eq!(&data, &request.data);
+        // the rollover amount is owed in native currency => the currency cannot be changed
+        let set_currency = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_currency(Currency::PSP22(token_address)));
+        let result = client.call(&ink_e2e::alice(), set_currency, 0, None).await;
+        assert!(result.is_err(), "the currency is in use");
 
-        // Bob signs the message
-        let keypair = subxt_signer::ecdsa::dev::bob();
-        let signature = keypair.sign(&scale::Encode::encode(&request)).0;
+        Ok(())
+    }
 
-        // do the meta tx: charlie sends the message
-        let meta_tx_rollup_cond_eq =
-            build_message::<lotto_contract::ContractRef>(contract_id.clone())
-                .call(|contract| contract.meta_tx_rollup_cond_eq(request.clone(), signature));
+    #[ink_e2e::test(
+        additional_contracts = "contracts/lotto/Cargo.toml contracts/psp22_token/Cargo.toml"
+    )]
+    async fn test_psp22_currency(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given a PSP22 token minted for alice
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        let token_constructor = psp22_token::ContractRef::new(1_000);
+        let token_id = client
+            .instantiate("psp22_token", &ink_e2e::alice(), token_constructor, 0, None)
+            .await
+            .expect("instantiate token failed")
+            .account_id;
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+
+        let token_balance_of = |account: AccountId| {
+            build_message::<psp22_token::ContractRef>(token_id.clone())
+                .call(move |token| token.balance_of(account))
+        };
+
+        // alice gives 100 tokens to dave
+        let transfer = build_message::<psp22_token::ContractRef>(token_id.clone())
+            .call(|token| token.transfer(dave_address, 100, vec![]));
         client
-            .call(&ink_e2e::charlie(), meta_tx_rollup_cond_eq, 0, None)
+            .call(&ink_e2e::alice(), transfer, 0, None)
             .await
-            .expect("meta tx rollup cond eq should not failed");
+            .expect("transfer token failed");
 
-        // do it again => it must failed
-        let meta_tx_rollup_cond_eq =
-            build_message::<lotto_contract::ContractRef>(contract_id.clone())
-                .call(|contract| contract.meta_tx_rollup_cond_eq(request.clone(), signature));
-        let result = client
-            .call(&ink_e2e::charlie(), meta_tx_rollup_cond_eq, 0, None)
-            .await;
+        // the tickets are paid with the token
+        let set_currency = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_currency(Currency::PSP22(token_id)));
+        client
+            .call(&ink_e2e::alice(), set_currency, 0, None)
+            .await
+            .expect("set currency failed");
+        let config = Config {
+            ticket_price: 10,
+            ..default_config()
+        };
+        alice_sets_config(&mut client, &contract_id, config).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+
+        // dave cannot pay in native currency
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, results.clone()));
+        let result = client.call(&ink_e2e::dave(), participate, 10, None).await;
+        assert!(result.is_err(), "the tickets are paid with the token");
+
+        // dave cannot pay without allowance
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, results.clone()));
+        let result = client.call(&ink_e2e::dave(), participate, 0, None).await;
+        assert!(result.is_err(), "the lotto contract is not approved");
+
+        // dave approves the lotto contract and pays the ticket (transfer_from)
+        let approve = build_message::<psp22_token::ContractRef>(token_id.clone())
+            .call(|token| token.approve(contract_id, 10));
+        client
+            .call(&ink_e2e::dave(), approve, 0, None)
+            .await
+            .expect("approve failed");
+        participates(&mut client, &contract_id, &ink_e2e::dave(), results.clone()).await;
+        assert_eq!(
+            90,
+            client
+                .call_dry_run(&ink_e2e::alice(), &token_balance_of(dave_address), 0, None)
+                .await
+                .return_value()
+        );
+        assert_eq!(
+            10,
+            client
+                .call_dry_run(&ink_e2e::alice(), &token_balance_of(contract_id), 0, None)
+                .await
+                .return_value()
+        );
+
+        // the balance of the contract is read in the token: only the tokens owed to nobody are available
+        let transfer = build_message::<psp22_token::ContractRef>(token_id.clone())
+            .call(|token| token.transfer(contract_id, 5, vec![]));
+        client
+            .call(&ink_e2e::alice(), transfer, 0, None)
+            .await
+            .expect("transfer token failed");
+        let get_available_balance =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.get_available_balance());
+        assert_eq!(
+            5,
+            client
+                .call_dry_run(&ink_e2e::alice(), &get_available_balance, 0, None)
+                .await
+                .return_value()
+        );
+
+        // dave wins and claims the reward in token (transfer)
+        alice_stops_raffle(&mut client, &contract_id).await;
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+        bob_sends_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results,
+            vec![dave_address],
+        )
+        .await;
+        let claim = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim(None));
+        client
+            .call(&ink_e2e::dave(), claim, 0, None)
+            .await
+            .expect("claim failed");
+        assert_eq!(
+            100,
+            client
+                .call_dry_run(&ink_e2e::alice(), &token_balance_of(dave_address), 0, None)
+                .await
+                .return_value()
+        );
+        assert_eq!(
+            5,
+            client
+                .call_dry_run(&ink_e2e::alice(), &token_balance_of(contract_id), 0, None)
+                .await
+                .return_value()
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_prize_tiers(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+
+        // configure the raffle with two prize tiers: 70% for 4/4 and 30% for 3/4
+        let config = Config {
+            prize_tiers: vec![
+                PrizeTier {
+                    nb_matching_numbers: 4,
+                    nb_matching_bonus_numbers: 0,
+                    share: 70,
+                },
+                PrizeTier {
+                    nb_matching_numbers: 3,
+                    nb_matching_bonus_numbers: 0,
+                    share: 30,
+                },
+            ],
+            ..default_config()
+        };
+        alice_sets_config(&mut client, &contract_id, config).await;
+
+        // bob is granted as attestor
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+
+        // fund the contract to have rewards
+        fund(&mut client, &contract_id, 100).await;
+
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+
+        // dave and charlie participate
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::dave(),
+            vec![5, 40, 8, 2],
+        )
+        .await;
+
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::charlie(),
+            vec![5, 40, 8, 3],
+        )
+        .await;
+
+        // stop the raffle and send the results
+        alice_stops_raffle(&mut client, &contract_id).await;
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+
+        // send the winners: dave matches 4 numbers, charlie matches 3 numbers
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
+        bob_sends_tiered_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results,
+            4,
+            vec![(4, 0), (3, 0)],
+            vec![(4, 0, vec![dave_address]), (3, 0, vec![charlie_address])],
+        )
+        .await;
+        assert_eq!(
+            Status::Closed,
+            get_current_status(&mut client, &contract_id).await
+        );
+
+        // check the rewards of each tier
+        assert_eq!(
+            Some(70),
+            get_pending_rewards_from(&mut client, &contract_id, &dave_address).await
+        );
+        assert_eq!(
+            Some(30),
+            get_pending_rewards_from(&mut client, &contract_id, &charlie_address).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_bonus_numbers(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+
+        // configure the raffle with 4 numbers between 1 and 50 and 1 bonus number between 1 and 10
+        let config = Config {
+            prize_tiers: vec![
+                PrizeTier {
+                    nb_matching_numbers: 4,
+                    nb_matching_bonus_numbers: 1,
+                    share: 60,
+                },
+                PrizeTier {
+                    nb_matching_numbers: 4,
+                    nb_matching_bonus_numbers: 0,
+                    share: 40,
+                },
+            ],
+            bonus_pool: Some(BonusPool {
+                nb_numbers: 1,
+                min_number: 1,
+                max_number: 10,
+            }),
+            ..default_config()
+        };
+        alice_sets_config(&mut client, &contract_id, config).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        fund(&mut client, &contract_id, 100).await;
+
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+
+        // the bonus number is missing
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, vec![5, 40, 8, 2]));
+        let result = client.call(&ink_e2e::dave(), participate, 0, None).await;
+        assert!(result.is_err(), "the bonus number is missing");
+
+        // the bonus number is out of the bonus pool
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, vec![5, 40, 8, 2, 11]));
+        let result = client.call(&ink_e2e::dave(), participate, 0, None).await;
+        assert!(result.is_err(), "the bonus number is out of the bonus pool");
+
+        // dave and charlie participate
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::dave(),
+            vec![5, 40, 8, 2, 7],
+        )
+        .await;
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::charlie(),
+            vec![5, 40, 8, 2, 3],
+        )
+        .await;
+
+        // stop the raffle and send the results with the bonus number
+        alice_stops_raffle(&mut client, &contract_id).await;
+        let results: Vec<Number> = vec![5, 40, 8, 2, 7];
+        let payload = LottoResponseMessage {
+            request: LottoRequestMessage {
+                game_id: GAME_ID,
+                raffle_id,
+                request: Request::DrawNumbers(4, 1, 50, Some((1, 1, 10)), DrawMode::Unordered),
+            },
+            response: Response::Numbers(results.clone()),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        client
+            .call(&ink_e2e::bob(), rollup_cond_eq, 0, None)
+            .await
+            .expect("send result failed");
+        assert_eq!(
+            Some(results.clone()),
+            get_results(&mut client, &contract_id, raffle_id).await
+        );
+
+        // dave matches the bonus number, charlie does not
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
+        bob_sends_tiered_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results,
+            4,
+            vec![(4, 1), (4, 0)],
+            vec![(4, 1, vec![dave_address]), (4, 0, vec![charlie_address])],
+        )
+        .await;
+
+        assert_eq!(
+            Some(60),
+            get_pending_rewards_from(&mut client, &contract_id, &dave_address).await
+        );
+        assert_eq!(
+            Some(40),
+            get_pending_rewards_from(&mut client, &contract_id, &charlie_address).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_ordered_draw(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+
+        // configure a pick-3 game: 3 digits between 0 and 9 matching in the same order
+        let config = Config {
+            nb_numbers: 3,
+            min_number: 0,
+            max_number: 9,
+            draw_mode: DrawMode::Ordered,
+            ..default_config()
+        };
+        alice_sets_config(&mut client, &contract_id, config).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+
+        // the digits can be repeated
+        participates(&mut client, &contract_id, &ink_e2e::dave(), vec![1, 1, 3]).await;
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::charlie(),
+            vec![3, 1, 1],
+        )
+        .await;
+
+        // stop the raffle and send the results
+        alice_stops_raffle(&mut client, &contract_id).await;
+        let results: Vec<Number> = vec![1, 1, 3];
+        let payload = LottoResponseMessage {
+            request: LottoRequestMessage {
+                game_id: GAME_ID,
+                raffle_id,
+                request: Request::DrawNumbers(3, 0, 9, None, DrawMode::Ordered),
+            },
+            response: Response::Numbers(results.clone()),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        client
+            .call(&ink_e2e::bob(), rollup_cond_eq, 0, None)
+            .await
+            .expect("send result failed");
+
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
+
+        // charlie has the same digits in another order => the response must be rejected
+        for winners in [vec![charlie_address], vec![dave_address]] {
+            let payload = LottoResponseMessage {
+                request: LottoRequestMessage {
+                    game_id: GAME_ID,
+                    raffle_id,
+                    request: Request::CheckTieredWinners(
+                        results.clone(),
+                        3,
+                        vec![(3, 0)],
+                        DrawMode::Ordered,
+                    ),
+                },
+                response: Response::TieredWinners(vec![(3, 0, winners.clone())]),
+            };
+            let actions = vec![HandleActionInput::Reply(payload.encode())];
+            let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+            let result = client.call(&ink_e2e::bob(), rollup_cond_eq, 0, None).await;
+            // only dave is the winner
+            assert_eq!(winners == vec![dave_address], result.is_ok());
+        }
+
+        assert_eq!(
+            Status::Closed,
+            get_current_status(&mut client, &contract_id).await
+        );
+        assert_eq!(
+            Some(vec![dave_address]),
+            get_winners(&mut client, &contract_id, raffle_id).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_quick_pick(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given a game using the quick-pick tickets
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        let config = Config {
+            quick_pick: true,
+            ..default_config()
+        };
+        alice_sets_config(&mut client, &contract_id, config).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+
+        // the seed is not received yet => dave cannot use the quick-pick
+        let participate_quick_pick =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.participate_quick_pick(GAME_ID, 3));
+        let result = client
+            .call(&ink_e2e::dave(), participate_quick_pick, 0, None)
+            .await;
+        assert!(result.is_err(), "the seed must be received");
+
+        // bob sends the seed
+        let payload = LottoResponseMessage {
+            request: LottoRequestMessage {
+                game_id: GAME_ID,
+                raffle_id,
+                request: Request::DrawQuickPickSeed,
+            },
+            response: Response::QuickPickSeed([7; 32]),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        client
+            .call(&ink_e2e::bob(), rollup_cond_eq, 0, None)
+            .await
+            .expect("send seed failed");
+
+        // the number of generated tickets is capped
+        for count in [0, 51] {
+            let participate_quick_pick =
+                build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                    .call(|contract| contract.participate_quick_pick(GAME_ID, count));
+            let result = client
+                .call(&ink_e2e::dave(), participate_quick_pick, 0, None)
+                .await;
+            assert!(result.is_err(), "incorrect number of tickets");
+        }
+
+        // dave participates with 3 generated tickets
+        let participate_quick_pick =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.participate_quick_pick(GAME_ID, 3));
+        let result = client
+            .call(&ink_e2e::dave(), participate_quick_pick, 0, None)
+            .await
+            .expect("Participate quick-pick failed");
+        assert!(result.contains_event("Contracts", "ContractEmitted"));
+
+        // the generated tickets respect the config
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let participations =
+            get_participations_of(&mut client, &contract_id, &dave_address, raffle_id).await;
+        assert_eq!(3, participations.len());
+        for participation in participations.iter() {
+            let numbers = &participation.numbers;
+            assert_eq!(4, numbers.len());
+            assert!(numbers.iter().all(|n| *n >= 1 && *n <= 50));
+            // distinct numbers saved sorted
+            assert!(numbers.windows(2).all(|w| w[0] < w[1]));
+        }
+        // each ticket is different
+        assert_ne!(participations[0], participations[1]);
+        assert_ne!(participations[1], participations[2]);
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_ticket_nft(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        fund(&mut client, &contract_id, 90).await;
+
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+
+        // dave participates three times => the tickets are minted for dave
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::dave(),
+            vec![5, 40, 8, 2],
+        )
+        .await;
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::dave(),
+            vec![2, 8, 40, 5],
+        )
+        .await;
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::dave(),
+            vec![40, 5, 2, 8],
+        )
+        .await;
+
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
+        let token_id = lotto_contract::get_ticket_token_id(raffle_id, 0);
+
+        let owner_of = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.owner_of(token_id.clone()));
+        let owner = client
+            .call_dry_run(&ink_e2e::alice(), &owner_of, 0, None)
+            .await
+            .return_value();
+        assert_eq!(Some(dave_address), owner);
+
+        // the metadata hold the raffle id and the numbers
+        let get_attribute = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_attribute(token_id.clone(), String::from("numbers")));
+        let numbers = client
+            .call_dry_run(&ink_e2e::alice(), &get_attribute, 0, None)
+            .await
+            .return_value();
+        assert_eq!(Some(String::from("2,5,8,40")), numbers);
+
+        // dave sells the first ticket to charlie and keeps the other ones
+        let transfer = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.transfer(charlie_address, token_id.clone(), vec![]));
+        client
+            .call(&ink_e2e::dave(), transfer, 0, None)
+            .await
+            .expect("transfer ticket failed");
+
+        // stop the raffle and send the results
+        alice_stops_raffle(&mut client, &contract_id).await;
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+
+        // the indexer still knows dave as participant
+        bob_sends_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results,
+            vec![dave_address],
+        )
+        .await;
+
+        // each winner is listed once
+        assert_eq!(
+            Some(vec![charlie_address, dave_address]),
+            get_winners(&mut client, &contract_id, raffle_id).await
+        );
+        // each winning ticket gets a share, paid to the current owner of the ticket
+        assert_eq!(
+            Some(30),
+            get_pending_rewards_from(&mut client, &contract_id, &charlie_address).await
+        );
+        assert_eq!(
+            Some(60),
+            get_pending_rewards_from(&mut client, &contract_id, &dave_address).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_bad_winners(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+
+        // dave and charlie participate
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::dave(),
+            vec![5, 40, 8, 2],
+        )
+        .await;
+
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::charlie(),
+            vec![5, 40, 8, 3],
+        )
+        .await;
+
+        alice_stops_raffle(&mut client, &contract_id).await;
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
+
+        // charlie does not match all numbers => the response must be rejected
+        // dave is duplicated => the response must be rejected
+        for winners in [
+            vec![dave_address, charlie_address],
+            vec![dave_address, dave_address],
+        ] {
+            let payload = LottoResponseMessage {
+                request: LottoRequestMessage {
+                    game_id: GAME_ID,
+                    raffle_id,
+                    request: Request::CheckWinners(results.clone()),
+                },
+                response: Response::Winners(winners),
+            };
+            let actions = vec![HandleActionInput::Reply(payload.encode())];
+            let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+            let result = client.call(&ink_e2e::bob(), rollup_cond_eq, 0, None).await;
+            assert!(result.is_err(), "the winners must be rejected");
+        }
+
+        // the prize tier is duplicated => the response must be rejected
+        let payload = LottoResponseMessage {
+            request: LottoRequestMessage {
+                game_id: GAME_ID,
+                raffle_id,
+                request: Request::CheckTieredWinners(
+                    results.clone(),
+                    4,
+                    vec![(4, 0)],
+                    DrawMode::Unordered,
+                ),
+            },
+            response: Response::TieredWinners(vec![
+                (4, 0, vec![dave_address]),
+                (4, 0, vec![dave_address]),
+            ]),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        let result = client.call(&ink_e2e::bob(), rollup_cond_eq, 0, None).await;
+        assert!(result.is_err(), "the prize tier must not be duplicated");
+
+        assert_eq!(
+            Status::WaitingWinners,
+            get_current_status(&mut client, &contract_id).await
+        );
+
+        // only dave is the winner
+        bob_sends_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results,
+            vec![dave_address],
+        )
+        .await;
+        assert_eq!(
+            Status::Closed,
+            get_current_status(&mut client, &contract_id).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_raffle_end_time(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given a raffle without end time
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        assert_eq!(
+            None,
+            get_end_time(&mut client, &contract_id, raffle_id).await
+        );
+
+        // charlie is not raffle operator and there is no end time => charlie cannot complete the raffle
+        let complete_raffle = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.complete_raffle(GAME_ID));
+        let result = client
+            .call(&ink_e2e::charlie(), complete_raffle, 0, None)
+            .await;
+        assert!(
+            result.is_err(),
+            "only the raffle operator can complete the raffle"
+        );
+
+        // given a raffle ending 1 ms after its start
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        let config = Config {
+            raffle_duration: Some(1),
+            ..default_config()
+        };
+        alice_sets_config(&mut client, &contract_id, config).await;
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        assert!(get_end_time(&mut client, &contract_id, raffle_id)
+            .await
+            .is_some());
+
+        // the raffle is ended => dave cannot participate
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, vec![5, 40, 8, 2]));
+        let result = client.call(&ink_e2e::dave(), participate, 0, None).await;
+        assert!(result.is_err(), "the raffle is ended");
+
+        // the raffle is ended => charlie can complete the raffle
+        let complete_raffle = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.complete_raffle(GAME_ID));
+        client
+            .call(&ink_e2e::charlie(), complete_raffle, 0, None)
+            .await
+            .expect("complete raffle failed");
+        assert_eq!(
+            Status::WaitingResults,
+            get_current_status(&mut client, &contract_id).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_claim_deadline(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given rewards to claim 1 ms after the winners are revealed
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        let config = Config {
+            claim_duration: Some(1),
+            ..default_config()
+        };
+        alice_sets_config(&mut client, &contract_id, config).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        fund(&mut client, &contract_id, 100).await;
+
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::dave(),
+            vec![5, 40, 8, 2],
+        )
+        .await;
+        alice_stops_raffle(&mut client, &contract_id).await;
+
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        bob_sends_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results,
+            vec![dave_address],
+        )
+        .await;
+        assert_eq!(0, get_rollover_amount(&mut client, &contract_id).await);
+
+        // the claim deadline is passed => dave cannot claim the rewards
+        assert_eq!(
+            None,
+            get_pending_rewards_from(&mut client, &contract_id, &dave_address).await
+        );
+        let claim = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim(None));
+        let result = client.call(&ink_e2e::dave(), claim, 0, None).await;
+        assert!(result.is_err(), "the rewards are expired");
+
+        // charlie is not raffle operator => charlie cannot sweep the expired rewards
+        let sweep_expired_rewards =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.sweep_expired_rewards(raffle_id));
+        let result = client
+            .call(&ink_e2e::charlie(), sweep_expired_rewards, 0, None)
+            .await;
+        assert!(
+            result.is_err(),
+            "only the raffle operator can sweep the rewards"
+        );
+
+        // alice sweeps the expired rewards into the next prize pool
+        let sweep_expired_rewards =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.sweep_expired_rewards(raffle_id));
+        let result = client
+            .call(&ink_e2e::alice(), sweep_expired_rewards, 0, None)
+            .await
+            .expect("sweep expired rewards failed");
+        assert!(result.contains_event("Contracts", "ContractEmitted"));
+
+        assert_eq!(100, get_rollover_amount(&mut client, &contract_id).await);
+        assert_eq!(
+            0,
+            get_total_pending_rewards(&mut client, &contract_id).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_rewards_detail(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+
+        // dave wins 100 in the raffle 1 and 50 in the raffle 2
+        let mut raffle_ids = Vec::new();
+        for amount in [100, 50] {
+            fund(&mut client, &contract_id, amount).await;
+            let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+            participates(&mut client, &contract_id, &ink_e2e::dave(), results.clone()).await;
+            alice_stops_raffle(&mut client, &contract_id).await;
+            bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+            bob_sends_winners(
+                &mut client,
+                &contract_id,
+                raffle_id,
+                results.clone(),
+                vec![dave_address],
+            )
+            .await;
+            raffle_ids.push(raffle_id);
+        }
+
+        let get_pending_rewards_detail =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.get_pending_rewards_detail(dave_address));
+        let rewards = client
+            .call_dry_run(&ink_e2e::alice(), &get_pending_rewards_detail, 0, None)
+            .await
+            .return_value();
+        assert_eq!(vec![(raffle_ids[0], 100), (raffle_ids[1], 50)], rewards);
+
+        // dave claims only the reward of the raffle 2
+        let claim = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim(Some(raffle_ids[1])));
+        client
+            .call(&ink_e2e::dave(), claim, 0, None)
+            .await
+            .expect("claim rewards failed");
+
+        let rewards = client
+            .call_dry_run(&ink_e2e::alice(), &get_pending_rewards_detail, 0, None)
+            .await
+            .return_value();
+        assert_eq!(vec![(raffle_ids[0], 100)], rewards);
+
+        // the reward of the raffle 2 cannot be claimed twice
+        let claim = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim(Some(raffle_ids[1])));
+        let result = client.call(&ink_e2e::dave(), claim, 0, None).await;
+        assert!(result.is_err(), "the reward is already claimed");
+
+        // dave claims all remaining rewards
+        check_and_claim_rewards(&mut client, &contract_id, &ink_e2e::dave(), 100).await;
+        let rewards = client
+            .call_dry_run(&ink_e2e::alice(), &get_pending_rewards_detail, 0, None)
+            .await
+            .return_value();
+        assert!(rewards.is_empty());
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_claim_from_and_to(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
+        let eve_address = ink::primitives::AccountId::from(ink_e2e::eve().public_key().0);
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+
+        // dave wins 100 in the raffle 1 and 50 in the raffle 2
+        let mut raffle_ids = Vec::new();
+        for amount in [100, 50] {
+            fund(&mut client, &contract_id, amount).await;
+            let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+            participates(&mut client, &contract_id, &ink_e2e::dave(), results.clone()).await;
+            alice_stops_raffle(&mut client, &contract_id).await;
+            bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+            bob_sends_winners(
+                &mut client,
+                &contract_id,
+                raffle_id,
+                results.clone(),
+                vec![dave_address],
+            )
+            .await;
+            raffle_ids.push(raffle_id);
+        }
+
+        // charlie is not the delegate of dave => charlie cannot claim for dave
+        let claim_from = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim_from(dave_address, Some(raffle_ids[0])));
+        let result = client.call(&ink_e2e::charlie(), claim_from, 0, None).await;
+        assert!(result.is_err(), "only the delegate can claim for dave");
+
+        // dave registers charlie as delegate
+        let set_claim_delegate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_claim_delegate(Some(charlie_address)));
+        client
+            .call(&ink_e2e::dave(), set_claim_delegate, 0, None)
+            .await
+            .expect("set claim delegate failed");
+
+        // charlie claims the reward of the raffle 1 for dave
+        let dave_balance_before = client
+            .balance(dave_address)
+            .await
+            .expect("getting balance failed");
+        let claim_from = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim_from(dave_address, Some(raffle_ids[0])));
+        client
+            .call(&ink_e2e::charlie(), claim_from, 0, None)
+            .await
+            .expect("claim from failed");
+        let dave_balance_after = client
+            .balance(dave_address)
+            .await
+            .expect("getting balance failed");
+        assert_eq!(dave_balance_before + 100, dave_balance_after);
+
+        // dave sends the reward of the raffle 2 to eve
+        let eve_balance_before = client
+            .balance(eve_address)
+            .await
+            .expect("getting balance failed");
+        let claim_to = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim_to(eve_address, Some(raffle_ids[1])));
+        client
+            .call(&ink_e2e::dave(), claim_to, 0, None)
+            .await
+            .expect("claim to failed");
+        let eve_balance_after = client
+            .balance(eve_address)
+            .await
+            .expect("getting balance failed");
+        assert_eq!(eve_balance_before + 50, eve_balance_after);
+
+        assert_eq!(
+            None,
+            get_pending_rewards_from(&mut client, &contract_id, &dave_address).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_config_between_raffles(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+
+        let new_config = Config {
+            nb_numbers: 5,
+            max_number: 40,
+            ..default_config()
+        };
+
+        // the config cannot be updated while the raffle is ongoing
+        fund(&mut client, &contract_id, 100).await;
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        let set_config = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_config(GAME_ID, new_config.clone()));
+        let result = client.call(&ink_e2e::alice(), set_config, 0, None).await;
+        assert!(
+            result.is_err(),
+            "the config cannot be updated during a raffle"
+        );
+
+        // dave wins the raffle, the raffle is closed
+        participates(&mut client, &contract_id, &ink_e2e::dave(), results.clone()).await;
+        alice_stops_raffle(&mut client, &contract_id).await;
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+        bob_sends_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results.clone(),
+            vec![dave_address],
+        )
+        .await;
+        assert_eq!(
+            Status::Closed,
+            get_current_status(&mut client, &contract_id).await
+        );
+
+        // the config can be updated between two raffles
+        alice_sets_config(&mut client, &contract_id, new_config.clone()).await;
+
+        // a late response for the first raffle is still checked with its config and ignored
+        let payload = LottoResponseMessage {
+            request: LottoRequestMessage {
+                game_id: GAME_ID,
+                raffle_id,
+                request: Request::DrawNumbers(4, 1, 50, None, DrawMode::Unordered),
+            },
+            response: Response::Numbers(results.clone()),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        client
+            .call(&ink_e2e::bob(), rollup_cond_eq, 0, None)
+            .await
+            .expect("the existing results should be ignored");
+        let new_raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+
+        // the tickets follow the new config
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::dave(),
+            vec![5, 40, 8, 2, 12],
+        )
+        .await;
+
+        // the config used by each raffle is kept
+        let get_config_of = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_config_of(raffle_id));
+        let config = client
+            .call_dry_run(&ink_e2e::alice(), &get_config_of, 0, None)
+            .await
+            .return_value()
+            .expect("no config for the first raffle");
+        assert_eq!(4, config.nb_numbers);
+        assert_eq!(50, config.max_number);
+
+        let get_config_of = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_config_of(new_raffle_id));
+        let config = client
+            .call_dry_run(&ink_e2e::alice(), &get_config_of, 0, None)
+            .await
+            .return_value();
+        assert_eq!(Some(new_config), config);
+
+        // the results must be drawn with the config of the raffle
+        alice_stops_raffle(&mut client, &contract_id).await;
+        let bad_results: Vec<Number> = vec![5, 40, 8, 2];
+        let payload = LottoResponseMessage {
+            request: LottoRequestMessage {
+                game_id: GAME_ID,
+                raffle_id: new_raffle_id,
+                request: Request::DrawNumbers(4, 1, 50, None, DrawMode::Unordered),
+            },
+            response: Response::Numbers(bad_results),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        let result = client.call(&ink_e2e::bob(), rollup_cond_eq, 0, None).await;
+        assert!(
+            result.is_err(),
+            "the results were drawn with another config"
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_scheduled_config(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+
+        let new_config = Config {
+            nb_numbers: 6,
+            max_number: 49,
+            ..default_config()
+        };
+
+        // the new config is scheduled while the raffle is ongoing
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        let schedule_config = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.schedule_config(GAME_ID, new_config.clone()));
+        let result = client
+            .call(&ink_e2e::alice(), schedule_config, 0, None)
+            .await
+            .expect("schedule config failed");
+        assert!(result.contains_event("Contracts", "ContractEmitted"));
+
+        let get_scheduled_config =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.get_scheduled_config(GAME_ID));
+        let scheduled_config = client
+            .call_dry_run(&ink_e2e::alice(), &get_scheduled_config, 0, None)
+            .await
+            .return_value();
+        assert_eq!(Some(new_config.clone()), scheduled_config);
+
+        // the ongoing raffle continues with its own config
+        participates(&mut client, &contract_id, &ink_e2e::dave(), results.clone()).await;
+        alice_stops_raffle(&mut client, &contract_id).await;
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+
+        // no winner => the next raffle starts with the scheduled config
+        bob_sends_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results.clone(),
+            vec![],
+        )
+        .await;
+        let new_raffle_id = get_current_raffle_id(&mut client, &contract_id).await;
+        assert_ne!(raffle_id, new_raffle_id);
+
+        let get_config_of = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_config_of(raffle_id));
+        let config = client
+            .call_dry_run(&ink_e2e::alice(), &get_config_of, 0, None)
+            .await
+            .return_value()
+            .expect("no config for the first raffle");
+        assert_eq!(4, config.nb_numbers);
+
+        let get_config_of = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_config_of(new_raffle_id));
+        let config = client
+            .call_dry_run(&ink_e2e::alice(), &get_config_of, 0, None)
+            .await
+            .return_value();
+        assert_eq!(Some(new_config), config);
+
+        // the scheduled config has been consumed
+        let scheduled_config = client
+            .call_dry_run(&ink_e2e::alice(), &get_scheduled_config, 0, None)
+            .await
+            .return_value();
+        assert_eq!(None, scheduled_config);
+
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::dave(),
+            vec![5, 40, 8, 2, 12, 49],
+        )
+        .await;
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_pause(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+
+        fund(&mut client, &contract_id, 100).await;
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+
+        // only the admin can pause the contract
+        let pause = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.pause(Capability::Sales));
+        let result = client.call(&ink_e2e::bob(), pause, 0, None).await;
+        assert!(result.is_err(), "only the admin can pause the sales");
+
+        // the sales are paused
+        let pause = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.pause(Capability::Sales));
+        client
+            .call(&ink_e2e::alice(), pause, 0, None)
+            .await
+            .expect("pause sales failed");
+
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(GAME_ID, results.clone()));
+        let result = client.call(&ink_e2e::dave(), participate, 0, None).await;
+        assert!(result.is_err(), "the sales are paused");
+
+        // the sales are resumed
+        let unpause = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.unpause(Capability::Sales));
+        client
+            .call(&ink_e2e::alice(), unpause, 0, None)
+            .await
+            .expect("unpause sales failed");
+        participates(&mut client, &contract_id, &ink_e2e::dave(), results.clone()).await;
+        alice_stops_raffle(&mut client, &contract_id).await;
+
+        // the messages from the rollup are rejected while the rollup is paused
+        let pause = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.pause(Capability::Rollup));
+        client
+            .call(&ink_e2e::alice(), pause, 0, None)
+            .await
+            .expect("pause rollup failed");
+
+        let payload = LottoResponseMessage {
+            request: LottoRequestMessage {
+                game_id: GAME_ID,
+                raffle_id,
+                request: Request::DrawNumbers(4, 1, 50, None, DrawMode::Unordered),
+            },
+            response: Response::Numbers(results.clone()),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        let result = client.call(&ink_e2e::bob(), rollup_cond_eq, 0, None).await;
+        assert!(result.is_err(), "the rollup is paused");
+
+        let unpause = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.unpause(Capability::Rollup));
+        client
+            .call(&ink_e2e::alice(), unpause, 0, None)
+            .await
+            .expect("unpause rollup failed");
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+        bob_sends_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results.clone(),
+            vec![dave_address],
+        )
+        .await;
+
+        // the claims are paused
+        let pause = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.pause(Capability::Claims));
+        client
+            .call(&ink_e2e::alice(), pause, 0, None)
+            .await
+            .expect("pause claims failed");
+
+        let is_paused = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.is_paused(Capability::Claims));
+        let paused = client
+            .call_dry_run(&ink_e2e::alice(), &is_paused, 0, None)
+            .await
+            .return_value();
+        assert!(paused);
+
+        let claim = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim(None));
+        let result = client.call(&ink_e2e::dave(), claim, 0, None).await;
+        assert!(result.is_err(), "the claims are paused");
+
+        // the claims are resumed
+        let unpause = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.unpause(Capability::Claims));
+        client
+            .call(&ink_e2e::alice(), unpause, 0, None)
+            .await
+            .expect("unpause claims failed");
+        check_and_claim_rewards(&mut client, &contract_id, &ink_e2e::dave(), 100).await;
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_roles(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given charlie as config manager, dave as raffle operator, eve as treasurer and ferdie as attestor admin
+        let bob_address = ink::primitives::AccountId::from(ink_e2e::bob().public_key().0);
+        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let eve_address = ink::primitives::AccountId::from(ink_e2e::eve().public_key().0);
+        let ferdie_address = ink::primitives::AccountId::from(ink_e2e::ferdie().public_key().0);
+        let lotto_constructor = lotto_contract::ContractRef::new(
+            charlie_address,
+            dave_address,
+            eve_address,
+            ferdie_address,
+        );
+        let contract_id = client
+            .instantiate(
+                "lotto_contract",
+                &ink_e2e::alice(),
+                lotto_constructor,
+                0,
+                None,
+            )
+            .await
+            .expect("instantiate failed")
+            .account_id;
+
+        let get_treasurer_role = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_treasurer_role());
+        let treasurer_role = client
+            .call_dry_run(&ink_e2e::alice(), &get_treasurer_role, 0, None)
+            .await
+            .return_value();
+        let has_role = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.has_role(treasurer_role, Some(eve_address)));
+        assert!(client
+            .call_dry_run(&ink_e2e::alice(), &has_role, 0, None)
+            .await
+            .return_value());
+
+        // only the config manager can set the config
+        let config = default_config();
+        let set_config = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_config(GAME_ID, config.clone()));
+        let result = client.call(&ink_e2e::alice(), set_config, 0, None).await;
+        assert!(
+            result.is_err(),
+            "only the config manager can set the config"
+        );
+
+        let set_config = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_config(GAME_ID, config.clone()));
+        client
+            .call(&ink_e2e::charlie(), set_config, 0, None)
+            .await
+            .expect("set config failed");
+
+        // only the attestor admin can register the attestors
+        let register_attestor = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.register_attestor(bob_address));
+        let result = client
+            .call(&ink_e2e::alice(), register_attestor, 0, None)
+            .await;
+        assert!(
+            result.is_err(),
+            "only the attestor admin can register the attestors"
+        );
+
+        let register_attestor = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.register_attestor(bob_address));
+        client
+            .call(&ink_e2e::ferdie(), register_attestor, 0, None)
+            .await
+            .expect("register attestor failed");
+
+        // only the raffle operator can start the raffle
+        let start_raffle = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.start_raffle(GAME_ID));
+        let result = client
+            .call(&ink_e2e::charlie(), start_raffle, 0, None)
+            .await;
+        assert!(
+            result.is_err(),
+            "only the raffle operator can start the raffle"
+        );
+
+        let start_raffle = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.start_raffle(GAME_ID));
+        client
+            .call(&ink_e2e::dave(), start_raffle, 0, None)
+            .await
+            .expect("start raffle failed");
+
+        // only the treasurer can withdraw the funds
+        let withdraw = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.withdraw(0));
+        let result = client.call(&ink_e2e::alice(), withdraw, 0, None).await;
+        assert!(result.is_err(), "only the treasurer can withdraw the funds");
+
+        let withdraw = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.withdraw(0));
+        client
+            .call(&ink_e2e::eve(), withdraw, 0, None)
+            .await
+            .expect("withdraw failed");
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_attestor_quorum(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given bob and charlie as attestors with a threshold of 2
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+
+        let register_attestor = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.register_attestor(charlie_address));
+        client
+            .call(&ink_e2e::alice(), register_attestor, 0, None)
+            .await
+            .expect("register attestor failed");
+
+        let set_attestor_threshold =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.set_attestor_threshold(2));
+        client
+            .call(&ink_e2e::alice(), set_attestor_threshold, 0, None)
+            .await
+            .expect("set attestor threshold failed");
+
+        fund(&mut client, &contract_id, 100).await;
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        participates(&mut client, &contract_id, &ink_e2e::dave(), results.clone()).await;
+        alice_stops_raffle(&mut client, &contract_id).await;
+
+        // the results sent by bob are not applied until another attestor sends the same ones
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+        assert_eq!(
+            None,
+            get_results(&mut client, &contract_id, raffle_id).await
+        );
+        assert_eq!(
+            Status::WaitingResults,
+            get_current_status(&mut client, &contract_id).await
+        );
+
+        let draw_numbers_request = LottoRequestMessage {
+            game_id: GAME_ID,
+            raffle_id,
+            request: Request::DrawNumbers(4, 1, 50, None, DrawMode::Unordered),
+        };
+        let mut request_hash = [0u8; 32];
+        ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+            &draw_numbers_request,
+            &mut request_hash,
+        );
+        let get_votes = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_votes(raffle_id, request_hash));
+        let votes = client
+            .call_dry_run(&ink_e2e::alice(), &get_votes, 0, None)
+            .await
+            .return_value();
+        assert_eq!(1, votes.len());
+
+        // a second vote of bob is not counted, even with another response,
+        // but the request is pushed again for the other attestors
+        let queue_tail = get_queue_tail(&mut client, &contract_id).await;
+        let payload = LottoResponseMessage {
+            request: draw_numbers_request.clone(),
+            response: Response::Numbers(vec![1, 2, 3, 4]),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        client
+            .call(&ink_e2e::bob(), rollup_cond_eq, 0, None)
+            .await
+            .expect("send results failed");
+        assert_eq!(
+            None,
+            get_results(&mut client, &contract_id, raffle_id).await
+        );
+        let votes = client
+            .call_dry_run(&ink_e2e::alice(), &get_votes, 0, None)
+            .await
+            .return_value();
+        assert_eq!(1, votes.len());
+        assert_eq!(
+            queue_tail + 1,
+            get_queue_tail(&mut client, &contract_id).await
+        );
+
+        // charlie sends the same results => the results are applied
+        let payload = LottoResponseMessage {
+            request: draw_numbers_request,
+            response: Response::Numbers(results.clone()),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        client
+            .call(&ink_e2e::charlie(), rollup_cond_eq, 0, None)
+            .await
+            .expect("send results failed");
+        assert_eq!(
+            Some(results.clone()),
+            get_results(&mut client, &contract_id, raffle_id).await
+        );
+
+        // the votes are cleared once the results are applied
+        let votes = client
+            .call_dry_run(&ink_e2e::alice(), &get_votes, 0, None)
+            .await
+            .return_value();
+        assert_eq!(0, votes.len());
+
+        // the winners also need the votes of two attestors
+        bob_sends_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results.clone(),
+            vec![dave_address],
+        )
+        .await;
+        assert_eq!(
+            None,
+            get_winners(&mut client, &contract_id, raffle_id).await
+        );
+
+        // the second vote is signed by the ecdsa account of bob (a third attestor) and relayed by dave
+        // use the ecsda account because we are not able to verify the sr25519 signature
+        let from = ink::primitives::AccountId::from(
+            Signer::<PolkadotConfig>::account_id(&subxt_signer::ecdsa::dev::bob()).0,
+        );
+        let register_attestor = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.register_attestor(from));
+        client
+            .call(&ink_e2e::alice(), register_attestor, 0, None)
+            .await
+            .expect("register attestor failed");
+
+        let payload = LottoResponseMessage {
+            request: LottoRequestMessage {
+                game_id: GAME_ID,
+                raffle_id,
+                request: Request::CheckWinners(results.clone()),
+            },
+            response: Response::Winners(vec![dave_address]),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let data = RollupCondEqMethodParams::encode(&(vec![], vec![], actions));
+        let prepare_meta_tx = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.prepare(from, data.clone()));
+        let (request, _hash) = client
+            .call_dry_run(&ink_e2e::dave(), &prepare_meta_tx, 0, None)
+            .await
+            .return_value()
+            .expect("Expected value when preparing meta tx");
+        let keypair = subxt_signer::ecdsa::dev::bob();
+        let signature = keypair.sign(&scale::Encode::encode(&request)).0;
+        let meta_tx_rollup_cond_eq =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.meta_tx_rollup_cond_eq(request.clone(), signature));
+        client
+            .call(&ink_e2e::dave(), meta_tx_rollup_cond_eq, 0, None)
+            .await
+            .expect("meta tx rollup cond eq failed");
+        assert_eq!(
+            Some(vec![dave_address]),
+            get_winners(&mut client, &contract_id, raffle_id).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_multi_games(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given a 4/50 game and a 6/49 game in the same contract
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+
+        const OTHER_GAME_ID: GameId = 2;
+        let config = Config {
+            nb_numbers: 6,
+            max_number: 49,
+            ..default_config()
+        };
+        let set_config = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_config(OTHER_GAME_ID, config.clone()));
+        client
+            .call(&ink_e2e::alice(), set_config, 0, None)
+            .await
+            .expect("set config failed");
+
+        // the raffle ids are shared by all games
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        let start_raffle = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.start_raffle(OTHER_GAME_ID));
+        let other_raffle_id = client
+            .call(&ink_e2e::alice(), start_raffle, 0, None)
+            .await
+            .expect("start raffle failed")
+            .return_value()
+            .expect("no value");
+        assert_ne!(raffle_id, other_raffle_id);
+
+        let get_game_id = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_game_id(other_raffle_id));
+        let game_id = client
+            .call_dry_run(&ink_e2e::alice(), &get_game_id, 0, None)
+            .await
+            .return_value();
+        assert_eq!(Some(OTHER_GAME_ID), game_id);
+
+        // the numbers are checked with the config of the game
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(OTHER_GAME_ID, vec![5, 40, 8, 2]));
+        let result = client.call(&ink_e2e::dave(), participate, 0, None).await;
+        assert!(result.is_err(), "the game needs 6 numbers");
+
+        let participate = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.participate(OTHER_GAME_ID, vec![5, 40, 8, 2, 12, 49]));
+        client
+            .call(&ink_e2e::dave(), participate, 0, None)
+            .await
+            .expect("participate failed");
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::eve(),
+            vec![5, 40, 8, 2],
+        )
+        .await;
+
+        assert_eq!(
+            1,
+            get_participations(&mut client, &contract_id, other_raffle_id, 0, 10)
+                .await
+                .len()
+        );
+
+        // complete the raffle of the first game
+        alice_stops_raffle(&mut client, &contract_id).await;
+        bob_sends_results(&mut client, &contract_id, raffle_id, vec![5, 40, 8, 2]).await;
+        assert_eq!(
+            Status::WaitingWinners,
+            get_current_status(&mut client, &contract_id).await
+        );
+
+        // the other game is still ongoing
+        let get_current_status = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_current_status(OTHER_GAME_ID));
+        let status = client
+            .call_dry_run(&ink_e2e::alice(), &get_current_status, 0, None)
+            .await
+            .return_value();
+        assert_eq!(Status::Ongoing, status);
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_fees(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+
+        // 10% of fees for eve
+        let eve_address = ink::primitives::AccountId::from(ink_e2e::eve().public_key().0);
+        let set_fee = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.set_fee(1_000, eve_address));
+        client
+            .call(&ink_e2e::alice(), set_fee, 0, None)
+            .await
+            .expect("set fee failed");
+
+        // fund the contract to have rewards
+        fund(&mut client, &contract_id, 100).await;
+
+        // the funds of the prize pool cannot be withdrawn
+        let withdraw = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.withdraw(1));
+        let result = client.call(&ink_e2e::alice(), withdraw, 0, None).await;
+        assert!(result.is_err(), "the prize pool cannot be withdrawn");
+
+        // dave wins the raffle
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        participates(
+            &mut client,
+            &contract_id,
+            &ink_e2e::dave(),
+            vec![5, 40, 8, 2],
+        )
+        .await;
+        alice_stops_raffle(&mut client, &contract_id).await;
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        bob_sends_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results,
+            vec![dave_address],
+        )
+        .await;
+
+        // dave wins 90 and the fees are 10
+        assert_eq!(
+            Some(90),
+            get_pending_rewards_from(&mut client, &contract_id, &dave_address).await
+        );
+        let get_total_fees = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.get_total_fees());
+        assert_eq!(
+            10,
+            client
+                .call_dry_run(&ink_e2e::alice(), &get_total_fees, 0, None)
+                .await
+                .return_value()
+        );
+
+        // the rewards and the fees cannot be withdrawn by the admin
+        let withdraw = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.withdraw(1));
+        let result = client.call(&ink_e2e::alice(), withdraw, 0, None).await;
+        assert!(result.is_err(), "the rewards and fees cannot be withdrawn");
+
+        // only the treasury can withdraw the fees
+        let withdraw_fees = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.withdraw_fees());
+        let result = client.call(&ink_e2e::alice(), withdraw_fees, 0, None).await;
+        assert!(result.is_err(), "only the treasury can withdraw the fees");
+
+        let withdraw_fees = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.withdraw_fees());
+        client
+            .call(&ink_e2e::eve(), withdraw_fees, 0, None)
+            .await
+            .expect("withdraw fees failed");
+        assert_eq!(
+            0,
+            client
+                .call_dry_run(&ink_e2e::alice(), &get_total_fees, 0, None)
+                .await
+                .return_value()
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_cancel_raffle(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        let config = Config {
+            ticket_price: 10,
+            ..default_config()
+        };
+        alice_sets_config(&mut client, &contract_id, config).await;
+
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+
+        // dave participates and pays three tickets
+        let participate =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone()).call(|contract| {
+                contract.participate_batch(
+                    GAME_ID,
+                    vec![vec![5, 40, 8, 2], vec![1, 2, 3, 4], vec![6, 7, 8, 9]],
+                )
+            });
+        client
+            .call(&ink_e2e::dave(), participate, 30, None)
+            .await
+            .expect("Participate failed");
+
+        // dave sells the second ticket to charlie
+        let charlie_address = ink::primitives::AccountId::from(ink_e2e::charlie().public_key().0);
+        let transfer =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone()).call(|contract| {
+                contract.transfer(
+                    charlie_address,
+                    lotto_contract::get_ticket_token_id(raffle_id, 1),
+                    vec![],
+                )
+            });
+        client
+            .call(&ink_e2e::dave(), transfer, 0, None)
+            .await
+            .expect("transfer ticket failed");
+
+        // dave cannot claim a refund for a raffle not cancelled
+        let claim_refund = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim_refund(raffle_id, vec![0]));
+        let result = client.call(&ink_e2e::dave(), claim_refund, 0, None).await;
+        assert!(result.is_err(), "the raffle is not cancelled");
+
+        // charlie is not raffle operator => charlie cannot cancel the raffle
+        let cancel_raffle = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.cancel_raffle(GAME_ID));
+        let result = client
+            .call(&ink_e2e::charlie(), cancel_raffle, 0, None)
+            .await;
+        assert!(
+            result.is_err(),
+            "only the raffle operator can cancel the raffle"
+        );
+
+        // the raffle cannot be cancelled before the cancellation delay (one day by default)
+        let cancel_raffle = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.cancel_raffle(GAME_ID));
+        let result = client.call(&ink_e2e::alice(), cancel_raffle, 0, None).await;
+        assert!(result.is_err(), "the raffle cannot be cancelled yet");
+
+        // the cancellation delay cannot be 0
+        let set_cancellation_delay =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.set_cancellation_delay(0));
+        let result = client
+            .call(&ink_e2e::alice(), set_cancellation_delay, 0, None)
+            .await;
+        assert!(result.is_err(), "the cancellation delay cannot be 0");
+
+        let set_cancellation_delay =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.set_cancellation_delay(1));
+        client
+            .call(&ink_e2e::alice(), set_cancellation_delay, 0, None)
+            .await
+            .expect("set cancellation delay failed");
+
+        // alice cancels the raffle
+        let cancel_raffle = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.cancel_raffle(GAME_ID));
+        client
+            .call(&ink_e2e::alice(), cancel_raffle, 0, None)
+            .await
+            .expect("cancel raffle failed");
+        assert_eq!(
+            Status::Cancelled,
+            get_current_status(&mut client, &contract_id).await
+        );
+
+        // dave cannot claim the refund of the ticket sold to charlie
+        let claim_refund = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim_refund(raffle_id, vec![0, 1]));
+        let result = client.call(&ink_e2e::dave(), claim_refund, 0, None).await;
+        assert!(result.is_err(), "only the owner of the ticket is refunded");
+
+        // dave claims the refund of the two remaining tickets at once, then charlie of the sold one
+        let claim_refund = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim_refund(raffle_id, vec![0, 2]));
+        client
+            .call(&ink_e2e::dave(), claim_refund, 0, None)
+            .await
+            .expect("claim refund failed");
+        assert_eq!(
+            10,
+            get_prize_pool(&mut client, &contract_id, raffle_id).await
+        );
+        let claim_refund = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim_refund(raffle_id, vec![1]));
+        client
+            .call(&ink_e2e::charlie(), claim_refund, 0, None)
+            .await
+            .expect("claim refund failed");
+        assert_eq!(
+            0,
+            get_prize_pool(&mut client, &contract_id, raffle_id).await
+        );
+
+        // dave cannot be refunded twice
+        let claim_refund = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.claim_refund(raffle_id, vec![0]));
+        let result = client.call(&ink_e2e::dave(), claim_refund, 0, None).await;
+        assert!(result.is_err(), "the refund is already claimed");
+
+        // a new raffle can start
+        assert_eq!(
+            raffle_id + 1,
+            alice_starts_raffle(&mut client, &contract_id).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_resend_request(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        alice_stops_raffle(&mut client, &contract_id).await;
+
+        // charlie is not raffle operator => charlie cannot push the request again
+        let resend_request = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.resend_request(GAME_ID));
+        let result = client
+            .call(&ink_e2e::charlie(), resend_request, 0, None)
+            .await;
+        assert!(
+            result.is_err(),
+            "only the raffle operator can push the request again"
+        );
+
+        // alice pushes the request again (no timeout configured)
+        let resend_request = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.resend_request(GAME_ID));
+        client
+            .call(&ink_e2e::alice(), resend_request, 0, None)
+            .await
+            .expect("resend request failed");
+
+        // bob sends the results
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+        assert_eq!(
+            Status::WaitingWinners,
+            get_current_status(&mut client, &contract_id).await
+        );
+
+        // bob answers the second request => the answer is ignored
+        let payload = LottoResponseMessage {
+            request: LottoRequestMessage {
+                game_id: GAME_ID,
+                raffle_id,
+                request: Request::DrawNumbers(4, 1, 50, None, DrawMode::Unordered),
+            },
+            response: Response::Numbers(vec![1, 2, 3, 4]),
+        };
+        let actions = vec![HandleActionInput::Reply(payload.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        client
+            .call(&ink_e2e::bob(), rollup_cond_eq, 0, None)
+            .await
+            .expect("the second answer should be ignored");
+
+        // only the first answer is applied
+        assert_eq!(
+            Some(results),
+            get_results(&mut client, &contract_id, raffle_id).await
+        );
+        assert_eq!(
+            Status::WaitingWinners,
+            get_current_status(&mut client, &contract_id).await
+        );
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_bad_attestor(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+
+        // bob is not granted as attestor => it should not be able to send a message
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], vec![]));
+        let result = client.call(&ink_e2e::bob(), rollup_cond_eq, 0, None).await;
+        assert!(
+            result.is_err(),
+            "only attestor should be able to send messages"
+        );
+
+        // bob is granted as attestor
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+
+        // then bob is able to send a message
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], vec![]));
+        let result = client
+            .call(&ink_e2e::bob(), rollup_cond_eq, 0, None)
+            .await
+            .expect("rollup cond eq failed");
+        // no event
+        assert!(!result.contains_event("Contracts", "ContractEmitted"));
+
+        Ok(())
+    }
+
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_bad_messages(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+
+        // bob is granted as attestor
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+
+        let actions = vec![HandleActionInput::Reply(58u128.encode())];
+        let rollup_cond_eq = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.rollup_cond_eq(vec![], vec![], actions.clone()));
+        let result = client.call(&ink_e2e::bob(), rollup_cond_eq, 0, None).await;
+        assert!(
+            result.is_err(),
+            "we should not be able to proceed bad messages"
+        );
+
+        Ok(())
+    }
+
+    ///
+    /// Test the meta transactions
+    /// Alice is the owner
+    /// Bob is the attestor
+    /// Charlie is the sender (ie the payer)
+    ///
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_meta_tx_rollup_cond_eq(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        let contract_id = alice_instantiates_contract(&mut client).await;
+
+        // Bob is the attestor
+        // use the ecsda account because we are not able to verify the sr25519 signature
+        let from = ink::primitives::AccountId::from(
+            Signer::<PolkadotConfig>::account_id(&subxt_signer::ecdsa::dev::bob()).0,
+        );
+
+        // add the role => it should be succeed
+        let grant_role = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.grant_role(ATTESTOR_ROLE, Some(from)));
+        client
+            .call(&ink_e2e::alice(), grant_role, 0, None)
+            .await
+            .expect("grant the attestor failed");
+
+        // prepare the meta transaction
+        let data = RollupCondEqMethodParams::encode(&(vec![], vec![], vec![]));
+        let prepare_meta_tx = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.prepare(from, data.clone()));
+        let result = client
+            .call(&ink_e2e::bob(), prepare_meta_tx, 0, None)
+            .await
+            .expect("We should be able to prepare the meta tx");
+
+        let (request, _hash) = result
+            .return_value()
+            .expect("Expected value when preparing meta tx");
+
+        assert_eq!(0, request.nonce);
+        assert_eq!(from, request.from);
+        assert_eq!(contract_id, request.to);
+        assert_eq!(&data, &request.data);
+
+        // Bob signs the message
+        let keypair = subxt_signer::ecdsa::dev::bob();
+        let signature = keypair.sign(&scale::Encode::encode(&request)).0;
+
+        // do the meta tx: charlie sends the message
+        let meta_tx_rollup_cond_eq =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.meta_tx_rollup_cond_eq(request.clone(), signature));
+        client
+            .call(&ink_e2e::charlie(), meta_tx_rollup_cond_eq, 0, None)
+            .await
+            .expect("meta tx rollup cond eq should not failed");
+
+        // do it again => it must failed
+        let meta_tx_rollup_cond_eq =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.meta_tx_rollup_cond_eq(request.clone(), signature));
+        let result = client
+            .call(&ink_e2e::charlie(), meta_tx_rollup_cond_eq, 0, None)
+            .await;
         assert!(
             result.is_err(),
             "This message should not be proceed because the nonce is obsolete"
@@ -774,4 +3103,102 @@ mod e2e_tests {
 
         Ok(())
     }
+
+    ///
+    /// Test the claim by meta transaction
+    /// Alice is the owner
+    /// Bob is the attestor
+    /// The ecdsa account of Bob is the winner
+    /// Charlie is the sender (ie the payer)
+    ///
+    #[ink_e2e::test(additional_contracts = "contracts/lotto/Cargo.toml")]
+    async fn test_meta_tx_claim(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+        // given
+        let contract_id = alice_instantiates_contract(&mut client).await;
+        alice_configures_contract(&mut client, &contract_id).await;
+        alice_grants_bob_as_attestor(&mut client, &contract_id).await;
+        // the reward must be above the existential deposit of the new account
+        let reward = 1_000_000_000_000;
+        fund(&mut client, &contract_id, reward).await;
+
+        // use the ecsda account because we are not able to verify the sr25519 signature
+        let from = ink::primitives::AccountId::from(
+            Signer::<PolkadotConfig>::account_id(&subxt_signer::ecdsa::dev::bob()).0,
+        );
+
+        // dave participates and gives the ticket to the ecdsa account
+        let raffle_id = alice_starts_raffle(&mut client, &contract_id).await;
+        let results: Vec<Number> = vec![5, 40, 8, 2];
+        participates(&mut client, &contract_id, &ink_e2e::dave(), results.clone()).await;
+        let transfer =
+            build_message::<lotto_contract::ContractRef>(contract_id.clone()).call(|contract| {
+                contract.transfer(
+                    from,
+                    lotto_contract::get_ticket_token_id(raffle_id, 0),
+                    vec![],
+                )
+            });
+        client
+            .call(&ink_e2e::dave(), transfer, 0, None)
+            .await
+            .expect("transfer ticket failed");
+
+        let dave_address = ink::primitives::AccountId::from(ink_e2e::dave().public_key().0);
+        alice_stops_raffle(&mut client, &contract_id).await;
+        bob_sends_results(&mut client, &contract_id, raffle_id, results.clone()).await;
+        bob_sends_winners(
+            &mut client,
+            &contract_id,
+            raffle_id,
+            results,
+            vec![dave_address],
+        )
+        .await;
+        assert_eq!(
+            Some(reward),
+            get_pending_rewards_from(&mut client, &contract_id, &from).await
+        );
+
+        // prepare the meta transaction
+        let data = (lotto_contract::META_TX_CLAIM_TAG, Some(raffle_id)).encode();
+        let prepare_meta_tx = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.prepare(from, data.clone()));
+        let (request, _hash) = client
+            .call_dry_run(&ink_e2e::charlie(), &prepare_meta_tx, 0, None)
+            .await
+            .return_value()
+            .expect("Expected value when preparing meta tx");
+        let keypair = subxt_signer::ecdsa::dev::bob();
+
+        // the data without the tag or with trailing bytes are rejected
+        for data in [
+            Some(raffle_id).encode(),
+            (lotto_contract::META_TX_CLAIM_TAG, Some(raffle_id), 0u8).encode(),
+        ] {
+            let mut bad_request = request.clone();
+            bad_request.data = data;
+            let signature = keypair.sign(&scale::Encode::encode(&bad_request)).0;
+            let meta_tx_claim = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+                .call(|contract| contract.meta_tx_claim(bad_request.clone(), signature));
+            let result = client
+                .call(&ink_e2e::charlie(), meta_tx_claim, 0, None)
+                .await;
+            assert!(result.is_err(), "the data of the claim are not correct");
+        }
+
+        // charlie relays the claim signed by the winner
+        let signature = keypair.sign(&scale::Encode::encode(&request)).0;
+        let meta_tx_claim = build_message::<lotto_contract::ContractRef>(contract_id.clone())
+            .call(|contract| contract.meta_tx_claim(request.clone(), signature));
+        client
+            .call(&ink_e2e::charlie(), meta_tx_claim, 0, None)
+            .await
+            .expect("meta tx claim failed");
+        assert_eq!(
+            None,
+            get_pending_rewards_from(&mut client, &contract_id, &from).await
+        );
+
+        Ok(())
+    }
 }
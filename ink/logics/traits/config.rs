@@ -1,15 +1,22 @@
 use crate::traits::error::RaffleError;
 use crate::traits::error::RaffleError::*;
-use crate::traits::Number;
-use openbrush::traits::Storage;
+use crate::traits::{GameId, Number, RaffleId};
+use ink::env::hash::Blake2x256;
+use ink::prelude::vec::Vec;
+use ink::storage::Mapping;
+use openbrush::traits::{Balance, Storage, Timestamp};
 
 #[derive(Default, Debug)]
 #[openbrush::storage_item]
 pub struct Data {
-    config: Option<Config>,
+    configs: Mapping<GameId, Config>,
+    /// snapshot of the config used by each raffle
+    raffle_configs: Mapping<RaffleId, Config>,
+    /// config applied when the next raffle of the game starts
+    scheduled_configs: Mapping<GameId, Config>,
 }
 
-#[derive(Debug, Eq, PartialEq, Copy, Clone, scale::Encode, scale::Decode)]
+#[derive(Default, Debug, Eq, PartialEq, Clone, scale::Encode, scale::Decode)]
 #[cfg_attr(
     feature = "std",
     derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
@@ -18,11 +25,169 @@ pub struct Config {
     pub nb_numbers: u8,
     pub min_number: Number,
     pub max_number: Number,
+    /// price of one ticket (0 means the participation is free)
+    pub ticket_price: Balance,
+    /// prize tiers for partial matches (empty means all-or-nothing jackpot)
+    pub prize_tiers: Vec<PrizeTier>,
+    /// duration of a raffle in milliseconds (None means no end time)
+    pub raffle_duration: Option<Timestamp>,
+    /// secondary pool for the bonus numbers (None means no bonus number)
+    pub bonus_pool: Option<BonusPool>,
+    /// how the numbers are drawn and matched
+    pub draw_mode: DrawMode,
+    /// maximum number of tickets for one account in a raffle (None means no limit)
+    pub max_tickets_per_account: Option<u32>,
+    /// maximum number of tickets in a raffle (None means no limit)
+    pub max_tickets_per_raffle: Option<u32>,
+    /// duration in milliseconds to claim the rewards of a raffle (None means no deadline)
+    pub claim_duration: Option<Timestamp>,
+    /// draw a seed at the start of each raffle to generate the quick-pick tickets
+    pub quick_pick: bool,
+}
+
+#[derive(Default, Debug, Eq, PartialEq, Copy, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum DrawMode {
+    /// distinct numbers, the order does not matter
+    #[default]
+    Unordered,
+    /// numbers can be repeated and must match at the same position (ie pick-3 / pick-4 games)
+    Ordered,
+}
+
+impl Config {
+    /// return the number of bonus numbers (0 without bonus pool)
+    pub fn get_nb_bonus_numbers(&self) -> u8 {
+        self.bonus_pool.map(|b| b.nb_numbers).unwrap_or_default()
+    }
+
+    /// check if the numbers (followed by the bonus numbers) respect the config
+    pub fn check_numbers(&self, numbers: &[Number]) -> Result<(), RaffleError> {
+        // check the numbers
+        let nb_numbers = numbers.len();
+
+        if nb_numbers != self.nb_numbers as usize + self.get_nb_bonus_numbers() as usize {
+            return Err(IncorrectNbNumbers);
+        }
+
+        let (numbers, bonus_numbers) = numbers.split_at(self.nb_numbers as usize);
+
+        for number in numbers.iter() {
+            if *number > self.max_number || *number < self.min_number {
+                return Err(IncorrectNumbers);
+            }
+        }
+
+        if let Some(bonus_pool) = self.bonus_pool {
+            for number in bonus_numbers.iter() {
+                if *number > bonus_pool.max_number || *number < bonus_pool.min_number {
+                    return Err(IncorrectNumbers);
+                }
+            }
+        }
+
+        // the numbers are drawn without repetition (except in ordered mode)
+        if self.draw_mode == DrawMode::Unordered
+            && (has_duplicates(numbers) || has_duplicates(bonus_numbers))
+        {
+            return Err(DuplicateNumbers);
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct BonusPool {
+    pub nb_numbers: u8,
+    pub min_number: Number,
+    pub max_number: Number,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct PrizeTier {
+    /// number of numbers matching the winning numbers
+    pub nb_matching_numbers: u8,
+    /// number of bonus numbers matching the winning bonus numbers
+    pub nb_matching_bonus_numbers: u8,
+    /// share of the prize pool (in percentage) split between the winners of this tier
+    pub share: u8,
+}
+
+fn has_duplicates(numbers: &[Number]) -> bool {
+    numbers
+        .iter()
+        .enumerate()
+        .any(|(i, n)| numbers[..i].contains(n))
+}
+
+/// derive the numbers between min and max from the entropy (distinct numbers in unordered mode),
+/// the pool is used to derive different numbers for the main numbers and the bonus numbers
+fn generate_random_numbers(
+    entropy: &[u8],
+    pool: u8,
+    nb_numbers: u8,
+    min_number: Number,
+    max_number: Number,
+    draw_mode: DrawMode,
+) -> Result<Vec<Number>, RaffleError> {
+    let range = (max_number as u32)
+        .checked_sub(min_number as u32)
+        .ok_or(SubOverFlow)?
+        .checked_add(1)
+        .ok_or(AddOverFlow)?;
+
+    let mut numbers: Vec<Number> = Vec::new();
+    for i in 0..nb_numbers {
+        let mut output = [0u8; 32];
+        ink::env::hash_encoded::<Blake2x256, _>(&(entropy, pool, i), &mut output);
+        // keep only 8 bytes to compute the random u64
+        let mut arr = [0u8; 8];
+        arr.copy_from_slice(&output[0..8]);
+        let rand_u64 = u64::from_le_bytes(arr);
+
+        let number = match draw_mode {
+            DrawMode::Ordered => min_number + (rand_u64 % range as u64) as Number,
+            DrawMode::Unordered => {
+                // pick the n-th number among the ones not drawn yet
+                let nb_remaining = range.checked_sub(i as u32).ok_or(IncorrectConfig)?;
+                let mut number = min_number + (rand_u64 % nb_remaining as u64) as Number;
+                let mut drawn_numbers = numbers.clone();
+                drawn_numbers.sort_unstable();
+                for drawn_number in drawn_numbers {
+                    if drawn_number <= number {
+                        number += 1;
+                    }
+                }
+                number
+            }
+        };
+        numbers.push(number);
+    }
+    Ok(numbers)
 }
 
 #[openbrush::trait_definition]
 pub trait RaffleConfig: Storage<Data> {
-    fn set_config(&mut self, config: Config) -> Result<(), RaffleError> {
+    fn set_config(&mut self, game_id: GameId, config: Config) -> Result<(), RaffleError> {
+        self.check_config(&config)?;
+        self.data::<Data>().configs.insert(game_id, &config);
+        Ok(())
+    }
+
+    /// check if the config is valid
+    fn check_config(&self, config: &Config) -> Result<(), RaffleError> {
         // check the config
         if config.nb_numbers == 0 {
             return Err(IncorrectConfig);
@@ -32,31 +197,143 @@ pub trait RaffleConfig: Storage<Data> {
             return Err(IncorrectConfig);
         }
 
-        self.data::<Data>().config = Some(config);
+        if config.raffle_duration == Some(0) || config.claim_duration == Some(0) {
+            return Err(IncorrectConfig);
+        }
+
+        if config.max_tickets_per_account == Some(0) || config.max_tickets_per_raffle == Some(0) {
+            return Err(IncorrectConfig);
+        }
+
+        // check the bonus pool
+        if let Some(bonus_pool) = config.bonus_pool {
+            if bonus_pool.nb_numbers == 0 || bonus_pool.min_number >= bonus_pool.max_number {
+                return Err(IncorrectConfig);
+            }
+        }
+        let nb_bonus_numbers = config.get_nb_bonus_numbers();
+
+        // in unordered mode, the numbers are distinct so the range must be large enough
+        if config.draw_mode == DrawMode::Unordered {
+            if config.nb_numbers as u32 > (config.max_number - config.min_number) as u32 + 1 {
+                return Err(IncorrectConfig);
+            }
+            if let Some(bonus_pool) = config.bonus_pool {
+                if bonus_pool.nb_numbers as u32
+                    > (bonus_pool.max_number - bonus_pool.min_number) as u32 + 1
+                {
+                    return Err(IncorrectConfig);
+                }
+            }
+        }
+
+        // check the prize tiers
+        let mut total_share: u16 = 0;
+        for (i, tier) in config.prize_tiers.iter().enumerate() {
+            if tier.nb_matching_numbers > config.nb_numbers
+                || tier.nb_matching_bonus_numbers > nb_bonus_numbers
+                || (tier.nb_matching_numbers == 0 && tier.nb_matching_bonus_numbers == 0)
+            {
+                return Err(IncorrectConfig);
+            }
+            // the same numbers of matching numbers cannot be used in two tiers
+            if config.prize_tiers[..i].iter().any(|t| {
+                t.nb_matching_numbers == tier.nb_matching_numbers
+                    && t.nb_matching_bonus_numbers == tier.nb_matching_bonus_numbers
+            }) {
+                return Err(IncorrectConfig);
+            }
+            total_share += tier.share as u16;
+        }
+
+        if total_share > 100 {
+            return Err(IncorrectConfig);
+        }
+
+        Ok(())
+    }
+
+    #[ink(message)]
+    fn get_config(&self, game_id: GameId) -> Option<Config> {
+        self.data::<Data>().configs.get(game_id)
+    }
+
+    /// save the config applied when the next raffle of the game starts
+    fn schedule_config(&mut self, game_id: GameId, config: Config) -> Result<(), RaffleError> {
+        self.check_config(&config)?;
+        self.data::<Data>()
+            .scheduled_configs
+            .insert(game_id, &config);
         Ok(())
     }
 
     #[ink(message)]
-    fn get_config(&self) -> Option<Config> {
-        self.data::<Data>().config
+    fn get_scheduled_config(&self, game_id: GameId) -> Option<Config> {
+        self.data::<Data>().scheduled_configs.get(game_id)
+    }
+
+    /// replace the config of the game by the scheduled one (if any) and return it
+    fn apply_scheduled_config(&mut self, game_id: GameId) -> Option<Config> {
+        let config = self.data::<Data>().scheduled_configs.get(game_id)?;
+        self.data::<Data>().scheduled_configs.remove(game_id);
+        self.data::<Data>().configs.insert(game_id, &config);
+        Some(config)
+    }
+
+    /// return the config of the game and throw an error of the config is missing
+    fn ensure_config(&self, game_id: GameId) -> Result<Config, RaffleError> {
+        self.data::<Data>().configs.get(game_id).ok_or(ConfigNotSet)
+    }
+
+    /// save the current config of the game as the config used by the raffle
+    fn save_config_of(&mut self, game_id: GameId, raffle_id: RaffleId) {
+        if let Some(config) = self.data::<Data>().configs.get(game_id) {
+            self.data::<Data>()
+                .raffle_configs
+                .insert(raffle_id, &config);
+        }
+    }
+
+    /// return the config used by the raffle
+    #[ink(message)]
+    fn get_config_of(&self, raffle_id: RaffleId) -> Option<Config> {
+        self.data::<Data>().raffle_configs.get(raffle_id)
     }
 
-    /// return the config and throw an error of the config is missing
-    fn ensure_config(&self) -> Result<Config, RaffleError> {
-        match self.data::<Data>().config {
-            None => Err(ConfigNotSet),
-            Some(config) => Ok(config),
+    /// return the config used by the raffle and throw an error of the config is missing
+    fn ensure_config_of(&self, raffle_id: RaffleId) -> Result<Config, RaffleError> {
+        self.data::<Data>()
+            .raffle_configs
+            .get(raffle_id)
+            .ok_or(ConfigNotSet)
+    }
+
+    /// return the prize tiers of the config used by the raffle.
+    /// Without prize tier, the whole prize pool is shared between the participants matching all numbers
+    fn ensure_prize_tiers(&self, raffle_id: RaffleId) -> Result<Vec<PrizeTier>, RaffleError> {
+        let config = self.ensure_config_of(raffle_id)?;
+
+        if config.prize_tiers.is_empty() {
+            return Ok(ink::prelude::vec![PrizeTier {
+                nb_matching_numbers: config.nb_numbers,
+                nb_matching_bonus_numbers: config.get_nb_bonus_numbers(),
+                share: 100,
+            }]);
         }
+
+        Ok(config.prize_tiers)
     }
 
-    /// check if the config is the same as the one given in parameter
-    fn ensure_same_config(&self, config: &Config) -> Result<(), RaffleError> {
-        // get the correct results for the given raffle
-        let this_config = self.ensure_config()?;
+    /// check if the draw parameters of the config used by the raffle are the same as the ones given in parameter
+    fn ensure_same_config(&self, raffle_id: RaffleId, config: &Config) -> Result<(), RaffleError> {
+        // get the config used by the given raffle
+        let this_config = self.ensure_config_of(raffle_id)?;
 
         if this_config.nb_numbers != config.nb_numbers
             || this_config.min_number != config.min_number
             || this_config.max_number != config.max_number
+            || this_config.bonus_pool != config.bonus_pool
+            || this_config.draw_mode != config.draw_mode
         {
             return Err(DifferentConfig);
         }
@@ -64,24 +341,79 @@ pub trait RaffleConfig: Storage<Data> {
         Ok(())
     }
 
-    /// check if the numbers respect the config
-    fn check_numbers(&mut self, numbers: &[Number]) -> Result<(), RaffleError> {
+    /// check if the numbers (followed by the bonus numbers) respect the config
+    fn check_numbers(&mut self, game_id: GameId, numbers: &[Number]) -> Result<(), RaffleError> {
         // check if the config is set
-        let config = self.ensure_config()?;
+        let config = self.ensure_config(game_id)?;
+        config.check_numbers(numbers)
+    }
 
-        // check the numbers
-        let nb_numbers = numbers.len();
+    /// return the canonical form of the ticket: in unordered mode, the numbers and
+    /// the bonus numbers are sorted so the same ticket is always saved the same way
+    fn canonicalize_numbers(
+        &self,
+        game_id: GameId,
+        mut numbers: Vec<Number>,
+    ) -> Result<Vec<Number>, RaffleError> {
+        let config = self.ensure_config(game_id)?;
 
-        if nb_numbers != config.nb_numbers as usize {
-            return Err(IncorrectNbNumbers);
+        if config.draw_mode == DrawMode::Unordered {
+            let split = (config.nb_numbers as usize).min(numbers.len());
+            let (main_numbers, bonus_numbers) = numbers.split_at_mut(split);
+            main_numbers.sort_unstable();
+            bonus_numbers.sort_unstable();
         }
 
-        for number in numbers.iter() {
-            if *number > config.max_number || *number < config.min_number {
-                return Err(IncorrectNumbers);
-            }
+        Ok(numbers)
+    }
+
+    /// generate a ticket (numbers followed by the bonus numbers) respecting the config of the game.
+    /// The same entropy always generates the same ticket
+    fn generate_numbers(
+        &self,
+        game_id: GameId,
+        entropy: &[u8],
+    ) -> Result<Vec<Number>, RaffleError> {
+        let config = self.ensure_config(game_id)?;
+
+        let mut numbers = generate_random_numbers(
+            entropy,
+            0,
+            config.nb_numbers,
+            config.min_number,
+            config.max_number,
+            config.draw_mode,
+        )?;
+
+        if let Some(bonus_pool) = config.bonus_pool {
+            let bonus_numbers = generate_random_numbers(
+                entropy,
+                1,
+                bonus_pool.nb_numbers,
+                bonus_pool.min_number,
+                bonus_pool.max_number,
+                config.draw_mode,
+            )?;
+            numbers.extend_from_slice(&bonus_numbers);
         }
 
-        Ok(())
+        Ok(numbers)
+    }
+
+    /// return the price of the given number of tickets
+    fn get_tickets_price(
+        &self,
+        game_id: GameId,
+        nb_tickets: usize,
+    ) -> Result<Balance, RaffleError> {
+        // check if the config is set
+        let config = self.ensure_config(game_id)?;
+
+        let price = config
+            .ticket_price
+            .checked_mul(nb_tickets as Balance)
+            .ok_or(MulOverFlow)?;
+
+        Ok(price)
     }
 }
@@ -6,19 +6,54 @@ pub enum RaffleError {
     AccessControlError(AccessControlError),
     IncorrectRaffle,
     IncorrectStatus,
+    TooLateToParticipate,
+    TooManyTicketsForAccount,
+    TooManyTicketsForRaffle,
+    RaffleNotEnded,
+    CancellationTooEarly,
+    IncorrectCancellationDelay,
+    RaffleNotCancelled,
     IncorrectConfig,
     ConfigNotSet,
     DifferentConfig,
     IncorrectNbNumbers,
     IncorrectNumbers,
+    DuplicateNumbers,
+    IncorrectTransferredValue,
+    CurrencyInUse,
+    IncorrectPrizeTier,
     DifferentResults,
     ExistingResults,
     ExistingWinners,
+    RequestNotTimedOut,
+    QuickPickSeedNotSet,
+    IncorrectNbTickets,
+    ExistingQuickPickSeed,
+    IncorrectWinner,
+    DuplicatedWinner,
+    DuplicatedPrizeTier,
     TransferError,
     AddOverFlow,
     SubOverFlow,
+    MulOverFlow,
     DivByZero,
     NoReward,
+    ClaimNotAllowed,
+    RewardExpired,
+    RewardNotExpired,
+    NoRefund,
+    NotTicketOwner,
+    IncorrectFeeRate,
+    TreasuryNotSet,
+    NotTreasury,
+    NoFees,
+    InsufficientBalance,
+    SalesPaused,
+    RollupPaused,
+    ClaimsPaused,
+    AlreadyPaused,
+    NotPaused,
+    IncorrectThreshold,
 }
 
 /// convertor from AccessControlError to RaffleError
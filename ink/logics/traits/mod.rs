@@ -1,11 +1,22 @@
 use openbrush::contracts::access_control::RoleType;
 
-pub const LOTTO_MANAGER_ROLE: RoleType = ink::selector_id!("LOTTO_MANAGER");
+/// role allowed to set the config of the games
+pub const CONFIG_MANAGER_ROLE: RoleType = ink::selector_id!("CONFIG_MANAGER");
+/// role allowed to start, complete and cancel the raffles
+pub const RAFFLE_OPERATOR_ROLE: RoleType = ink::selector_id!("RAFFLE_OPERATOR");
+/// role allowed to withdraw the funds and to set the fees
+pub const TREASURER_ROLE: RoleType = ink::selector_id!("TREASURER");
+/// role allowed to register the attestors of the offchain rollup
+pub const ATTESTOR_ADMIN_ROLE: RoleType = ink::selector_id!("ATTESTOR_ADMIN");
 
+pub type GameId = u32;
 pub type RaffleId = u32;
 pub type Number = u16;
+pub type TicketId = u32;
 
 pub mod config;
 pub mod error;
+pub mod pause;
+pub mod quorum;
 pub mod raffle;
 pub mod reward;
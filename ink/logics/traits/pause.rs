@@ -0,0 +1,63 @@
+use crate::traits::error::RaffleError;
+use crate::traits::error::RaffleError::*;
+use openbrush::traits::Storage;
+
+/// one switch per capability: the openbrush `pausable` storage item holds a single flag
+/// and a contract can embed only one of them, so it cannot pause the capabilities separately
+#[derive(Default, Debug)]
+#[openbrush::storage_item]
+pub struct Data {
+    sales_paused: bool,
+    rollup_paused: bool,
+    claims_paused: bool,
+}
+
+#[derive(Debug, Eq, PartialEq, Copy, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Capability {
+    /// sale of the tickets
+    Sales,
+    /// handling of the messages sent by the offchain rollup
+    Rollup,
+    /// claims of the rewards and the refunds
+    Claims,
+}
+
+#[openbrush::trait_definition]
+pub trait PauseManager: Storage<Data> {
+    #[ink(message)]
+    fn is_paused(&self, capability: Capability) -> bool {
+        let data = self.data::<Data>();
+        match capability {
+            Capability::Sales => data.sales_paused,
+            Capability::Rollup => data.rollup_paused,
+            Capability::Claims => data.claims_paused,
+        }
+    }
+
+    /// pause or resume the capability
+    fn set_paused(&mut self, capability: Capability, paused: bool) -> Result<(), RaffleError> {
+        if self.is_paused(capability) == paused {
+            return Err(if paused { AlreadyPaused } else { NotPaused });
+        }
+        let data = self.data::<Data>();
+        match capability {
+            Capability::Sales => data.sales_paused = paused,
+            Capability::Rollup => data.rollup_paused = paused,
+            Capability::Claims => data.claims_paused = paused,
+        }
+        Ok(())
+    }
+
+    /// throw an error if the capability is paused
+    fn ensure_not_paused(&self, capability: Capability) -> Result<(), RaffleError> {
+        if !self.is_paused(capability) {
+            return Ok(());
+        }
+        match capability {
+            Capability::Sales => Err(SalesPaused),
+            Capability::Rollup => Err(RollupPaused),
+            Capability::Claims => Err(ClaimsPaused),
+        }
+    }
+}
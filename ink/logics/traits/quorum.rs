@@ -0,0 +1,70 @@
+use crate::traits::error::RaffleError;
+use crate::traits::error::RaffleError::*;
+use crate::traits::RaffleId;
+use ink::prelude::vec::Vec;
+use ink::storage::Mapping;
+use openbrush::traits::{AccountId, Storage};
+
+#[derive(Default, Debug)]
+#[openbrush::storage_item]
+pub struct Data {
+    /// number of attestors who must send the same response (0 or 1 means a single attestor)
+    threshold: u32,
+    /// votes of the attestors (with the hash of their response) per raffle and hash of the request
+    votes: Mapping<(RaffleId, [u8; 32]), Vec<(AccountId, [u8; 32])>>,
+}
+
+#[openbrush::trait_definition]
+pub trait AttestorQuorum: Storage<Data> {
+    /// set the number of attestors who must send the same response before it is applied
+    fn set_attestor_threshold(&mut self, threshold: u32) -> Result<(), RaffleError> {
+        if threshold == 0 {
+            return Err(IncorrectThreshold);
+        }
+        self.data::<Data>().threshold = threshold;
+        Ok(())
+    }
+
+    #[ink(message)]
+    fn get_attestor_threshold(&self) -> u32 {
+        self.data::<Data>().threshold.max(1)
+    }
+
+    /// save the vote of the attestor for the response to the request and return the number of attestors
+    /// who sent the same response. Only the first vote of the attestor for the request is counted (None otherwise)
+    fn add_vote(
+        &mut self,
+        raffle_id: RaffleId,
+        request_hash: [u8; 32],
+        attestor: AccountId,
+        response_hash: [u8; 32],
+    ) -> Option<u32> {
+        let mut votes = self
+            .data::<Data>()
+            .votes
+            .get((raffle_id, request_hash))
+            .unwrap_or_default();
+        if votes.iter().any(|(a, _)| *a == attestor) {
+            return None;
+        }
+        votes.push((attestor, response_hash));
+        self.data::<Data>()
+            .votes
+            .insert((raffle_id, request_hash), &votes);
+        let nb_votes = votes.iter().filter(|(_, h)| *h == response_hash).count();
+        Some(nb_votes as u32)
+    }
+
+    /// remove the votes for the request once its response is applied
+    fn clear_votes(&mut self, raffle_id: RaffleId, request_hash: [u8; 32]) {
+        self.data::<Data>().votes.remove((raffle_id, request_hash));
+    }
+
+    #[ink(message)]
+    fn get_votes(&self, raffle_id: RaffleId, request_hash: [u8; 32]) -> Vec<(AccountId, [u8; 32])> {
+        self.data::<Data>()
+            .votes
+            .get((raffle_id, request_hash))
+            .unwrap_or_default()
+    }
+}
@@ -1,17 +1,43 @@
+use crate::traits::config::{Config, DrawMode, PrizeTier};
 use crate::traits::error::RaffleError;
 use crate::traits::error::RaffleError::*;
-use crate::traits::{Number, RaffleId};
+use crate::traits::{GameId, Number, RaffleId, TicketId};
 use ink::prelude::vec::Vec;
 use ink::storage::Mapping;
-use openbrush::traits::{AccountId, Storage};
+use openbrush::traits::{AccountId, Storage, Timestamp};
 
+/// the layout is not compatible with the single-game version (no migration): the contract must be redeployed
 #[derive(Default, Debug)]
 #[openbrush::storage_item]
 pub struct Data {
-    current_raffle_id: RaffleId,
-    status: Status,
+    last_raffle_id: RaffleId,
+    current_raffle_ids: Mapping<GameId, RaffleId>,
+    statuses: Mapping<GameId, Status>,
+    raffle_games: Mapping<RaffleId, GameId>,
     results: Mapping<RaffleId, Vec<Number>>,
     winners: Mapping<RaffleId, Vec<AccountId>>,
+    participations: Mapping<(RaffleId, TicketId), Participation>,
+    nb_participations: Mapping<RaffleId, u32>,
+    account_participations: Mapping<(AccountId, RaffleId, u32), TicketId>,
+    nb_account_participations: Mapping<(AccountId, RaffleId), u32>,
+    start_times: Mapping<RaffleId, Timestamp>,
+    end_times: Mapping<RaffleId, Timestamp>,
+    status_updated_at: Mapping<GameId, Timestamp>,
+    cancellation_delay: Timestamp,
+    cancelled_raffles: Mapping<RaffleId, bool>,
+    request_queued_at: Mapping<GameId, Timestamp>,
+    request_timeout: Timestamp,
+    quick_pick_seeds: Mapping<RaffleId, [u8; 32]>,
+}
+
+#[derive(Debug, Eq, PartialEq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub struct Participation {
+    pub participant: AccountId,
+    pub numbers: Vec<Number>,
 }
 
 #[derive(Default, Debug, Eq, PartialEq, Copy, Clone, scale::Encode, scale::Decode)]
@@ -26,47 +52,250 @@ pub enum Status {
     WaitingResults,
     WaitingWinners,
     Closed,
+    Cancelled,
+}
+
+/// return the number of numbers and the number of bonus numbers of the ticket matching the results
+pub fn count_matching_numbers(
+    nb_numbers: u8,
+    draw_mode: DrawMode,
+    numbers: &[Number],
+    results: &[Number],
+) -> (usize, usize) {
+    let (numbers, bonus_numbers) = numbers.split_at((nb_numbers as usize).min(numbers.len()));
+    let (results, bonus_results) = results.split_at((nb_numbers as usize).min(results.len()));
+    (
+        count_matching(draw_mode, numbers, results),
+        count_matching(draw_mode, bonus_numbers, bonus_results),
+    )
+}
+
+fn count_matching(draw_mode: DrawMode, numbers: &[Number], results: &[Number]) -> usize {
+    match draw_mode {
+        DrawMode::Unordered => numbers.iter().filter(|n| results.contains(n)).count(),
+        // in ordered mode, the numbers are compared position by position
+        DrawMode::Ordered => numbers
+            .iter()
+            .zip(results.iter())
+            .filter(|(n, r)| n == r)
+            .count(),
+    }
 }
 
 #[openbrush::trait_definition]
 pub trait Raffle: Storage<Data> {
-    /// Start a new raffle
-    fn start_new_raffle(&mut self) -> Result<RaffleId, RaffleError> {
+    /// Start a new raffle for the game, ending after the given duration (if any)
+    fn start_new_raffle(
+        &mut self,
+        game_id: GameId,
+        duration: Option<Timestamp>,
+    ) -> Result<RaffleId, RaffleError> {
         // check the status
-        if self.data::<Data>().status != Status::NotStarted
-            && self.data::<Data>().status != Status::Closed
-        {
+        let status = self.get_current_status(game_id);
+        if status != Status::NotStarted && status != Status::Closed && status != Status::Cancelled {
             return Err(RaffleError::IncorrectStatus);
         }
 
-        // increment the raffle id
-        let new_raffle_id = self.data::<Data>().current_raffle_id + 1;
+        // increment the raffle id, shared by all games
+        let new_raffle_id = self
+            .data::<Data>()
+            .last_raffle_id
+            .checked_add(1)
+            .ok_or(AddOverFlow)?;
 
-        self.data::<Data>().current_raffle_id = new_raffle_id;
-        self.data::<Data>().status = Status::Ongoing;
+        self.data::<Data>().last_raffle_id = new_raffle_id;
+        self.data::<Data>()
+            .current_raffle_ids
+            .insert(game_id, &new_raffle_id);
+        self.data::<Data>()
+            .raffle_games
+            .insert(new_raffle_id, &game_id);
+        self.set_status(game_id, Status::Ongoing);
+
+        // save the schedule
+        let start_time = Self::env().block_timestamp();
+        self.data::<Data>()
+            .start_times
+            .insert(new_raffle_id, &start_time);
+        if let Some(duration) = duration {
+            let end_time = start_time.checked_add(duration).ok_or(AddOverFlow)?;
+            self.data::<Data>()
+                .end_times
+                .insert(new_raffle_id, &end_time);
+        }
 
         Ok(new_raffle_id)
     }
 
-    /// Stop the current raffle
-    fn stop_current_raffle(&mut self) -> Result<(), RaffleError> {
+    /// Stop the current raffle of the game
+    fn stop_current_raffle(&mut self, game_id: GameId) -> Result<(), RaffleError> {
         // check the status
-        if self.data::<Data>().status != Status::Ongoing {
+        if self.get_current_status(game_id) != Status::Ongoing {
             return Err(RaffleError::IncorrectStatus);
         }
         // update the status
-        self.data::<Data>().status = Status::WaitingResults;
+        self.set_status(game_id, Status::WaitingResults);
         Ok(())
     }
 
+    /// Cancel the current raffle of the game if its status has not changed since the cancellation delay
+    fn cancel_current_raffle(&mut self, game_id: GameId) -> Result<RaffleId, RaffleError> {
+        // check the status
+        let status = self.get_current_status(game_id);
+        if status != Status::Ongoing
+            && status != Status::WaitingResults
+            && status != Status::WaitingWinners
+        {
+            return Err(RaffleError::IncorrectStatus);
+        }
+
+        // check the delay
+        let cancellable_at = self
+            .data::<Data>()
+            .status_updated_at
+            .get(game_id)
+            .unwrap_or_default()
+            .checked_add(self.data::<Data>().cancellation_delay)
+            .ok_or(AddOverFlow)?;
+        if Self::env().block_timestamp() < cancellable_at {
+            return Err(CancellationTooEarly);
+        }
+
+        let raffle_id = self.get_current_raffle_id(game_id);
+        self.data::<Data>()
+            .cancelled_raffles
+            .insert(raffle_id, &true);
+        self.set_status(game_id, Status::Cancelled);
+
+        Ok(raffle_id)
+    }
+
+    /// update the status of the current raffle of the game
+    fn set_status(&mut self, game_id: GameId, status: Status) {
+        self.data::<Data>().statuses.insert(game_id, &status);
+        self.data::<Data>()
+            .status_updated_at
+            .insert(game_id, &Self::env().block_timestamp());
+    }
+
+    #[ink(message)]
+    fn is_cancelled(&self, raffle_id: RaffleId) -> bool {
+        self.data::<Data>().cancelled_raffles.contains(raffle_id)
+    }
+
+    /// set the delay (in milliseconds) without status change before a raffle can be cancelled
+    fn set_cancellation_delay(&mut self, delay: Timestamp) -> Result<(), RaffleError> {
+        // without delay, a raffle could be cancelled as soon as the winning numbers are known
+        if delay == 0 {
+            return Err(IncorrectCancellationDelay);
+        }
+        self.data::<Data>().cancellation_delay = delay;
+        Ok(())
+    }
+
+    #[ink(message)]
+    fn get_cancellation_delay(&self) -> Timestamp {
+        self.data::<Data>().cancellation_delay
+    }
+
+    /// save when the last request of the game has been pushed in the queue
+    fn set_request_queued(&mut self, game_id: GameId) {
+        self.data::<Data>()
+            .request_queued_at
+            .insert(game_id, &Self::env().block_timestamp());
+    }
+
+    /// check if the last request of the game has been pushed in the queue for longer than the timeout
+    fn ensure_request_timed_out(&self, game_id: GameId) -> Result<(), RaffleError> {
+        let timed_out_at = self
+            .data::<Data>()
+            .request_queued_at
+            .get(game_id)
+            .unwrap_or_default()
+            .checked_add(self.data::<Data>().request_timeout)
+            .ok_or(AddOverFlow)?;
+        if Self::env().block_timestamp() < timed_out_at {
+            return Err(RequestNotTimedOut);
+        }
+        Ok(())
+    }
+
+    /// set the delay (in milliseconds) before a request without answer can be pushed again
+    fn set_request_timeout(&mut self, timeout: Timestamp) {
+        self.data::<Data>().request_timeout = timeout;
+    }
+
+    #[ink(message)]
+    fn get_request_timeout(&self) -> Timestamp {
+        self.data::<Data>().request_timeout
+    }
+
+    /// save the seed (delivered by the offchain rollup) used to generate the quick-pick tickets
+    fn set_quick_pick_seed(
+        &mut self,
+        raffle_id: RaffleId,
+        seed: [u8; 32],
+    ) -> Result<(), RaffleError> {
+        // check if the seed is already saved (ie the request has been answered)
+        if self.data::<Data>().quick_pick_seeds.contains(raffle_id) {
+            return Err(ExistingQuickPickSeed);
+        }
+
+        // check if the raffle exists
+        if !self.data::<Data>().raffle_games.contains(raffle_id) {
+            return Err(IncorrectRaffle);
+        }
+
+        self.data::<Data>()
+            .quick_pick_seeds
+            .insert(raffle_id, &seed);
+        Ok(())
+    }
+
+    #[ink(message)]
+    fn get_quick_pick_seed(&self, raffle_id: RaffleId) -> Option<[u8; 32]> {
+        self.data::<Data>().quick_pick_seeds.get(raffle_id)
+    }
+
+    #[ink(message)]
+    fn get_current_raffle_id(&self, game_id: GameId) -> RaffleId {
+        self.data::<Data>()
+            .current_raffle_ids
+            .get(game_id)
+            .unwrap_or_default()
+    }
+
+    #[ink(message)]
+    fn get_current_status(&self, game_id: GameId) -> Status {
+        self.data::<Data>()
+            .statuses
+            .get(game_id)
+            .unwrap_or_default()
+    }
+
+    /// return the game of the given raffle
+    #[ink(message)]
+    fn get_game_id(&self, raffle_id: RaffleId) -> Option<GameId> {
+        self.data::<Data>().raffle_games.get(raffle_id)
+    }
+
     #[ink(message)]
-    fn get_current_raffle_id(&self) -> RaffleId {
-        self.data::<Data>().current_raffle_id
+    fn get_start_time(&self, raffle_id: RaffleId) -> Option<Timestamp> {
+        self.data::<Data>().start_times.get(raffle_id)
     }
 
     #[ink(message)]
-    fn get_current_status(&self) -> Status {
-        self.data::<Data>().status
+    fn get_end_time(&self, raffle_id: RaffleId) -> Option<Timestamp> {
+        self.data::<Data>().end_times.get(raffle_id)
+    }
+
+    /// check if the end time of the current raffle of the game is passed
+    fn ensure_raffle_ended(&self, game_id: GameId) -> Result<(), RaffleError> {
+        let raffle_id = self.get_current_raffle_id(game_id);
+        match self.data::<Data>().end_times.get(raffle_id) {
+            Some(end_time) if Self::env().block_timestamp() >= end_time => Ok(()),
+            _ => Err(RaffleNotEnded),
+        }
     }
 
     #[ink(message)]
@@ -79,38 +308,41 @@ pub trait Raffle: Storage<Data> {
         self.data::<Data>().winners.get(raffle_id)
     }
 
-    /// save the results for the current raffle.
+    /// save the results for the current raffle of the game.
     fn set_results(
         &mut self,
+        game_id: GameId,
         raffle_id: RaffleId,
         results: Vec<Number>,
     ) -> Result<(), RaffleError> {
+        // check if the results are already saved (ie the request has been answered)
+        if self.data::<Data>().results.contains(raffle_id) {
+            return Err(ExistingResults);
+        }
+
         // check the raffle number
-        if self.data::<Data>().current_raffle_id != raffle_id {
+        if self.get_current_raffle_id(game_id) != raffle_id {
             return Err(RaffleError::IncorrectRaffle);
         }
 
         // check the status
-        if self.data::<Data>().status != Status::WaitingResults {
+        if self.get_current_status(game_id) != Status::WaitingResults {
             return Err(RaffleError::IncorrectStatus);
         }
 
-        match self.data::<Data>().results.get(raffle_id) {
-            Some(_) => Err(ExistingResults),
-            None => {
-                // save the results
-                self.data::<Data>().results.insert(raffle_id, &results);
-                // update the status
-                self.data::<Data>().status = Status::WaitingWinners;
-                Ok(())
-            }
-        }
+        // save the results
+        self.data::<Data>().results.insert(raffle_id, &results);
+        // update the status
+        self.set_status(game_id, Status::WaitingWinners);
+        Ok(())
     }
 
-    /// check if the saved results are the same as the ones given in parameter
+    /// check if the saved results are the same as the ones given in parameter.
+    /// In ordered mode, the numbers must be in the same order
     fn ensure_same_results(
         &mut self,
         raffle_id: RaffleId,
+        config: &Config,
         numbers: &[Number],
     ) -> Result<(), RaffleError> {
         // get the correct results for the given raffle
@@ -124,50 +356,244 @@ pub trait Raffle: Storage<Data> {
             return Err(DifferentResults);
         }
 
-        for i in 0..numbers.len() {
-            if numbers[i] != result[i] {
-                return Err(DifferentResults);
-            }
+        // all numbers match in both directions
+        let (nb_matching, nb_matching_bonus) =
+            count_matching_numbers(config.nb_numbers, config.draw_mode, numbers, &result);
+        let (nb_matched, nb_matched_bonus) =
+            count_matching_numbers(config.nb_numbers, config.draw_mode, &result, numbers);
+        if nb_matching + nb_matching_bonus != result.len()
+            || nb_matched + nb_matched_bonus != result.len()
+        {
+            return Err(DifferentResults);
         }
 
         Ok(())
     }
 
-    /// save the winners for the current raffle.
+    /// check if each account holds a ticket matching the numbers and the bonus numbers of the tier
+    /// and return all the matching tickets, each one with the account which bought it.
+    /// The first `nb_numbers` numbers are the main numbers, the next ones are the bonus numbers
+    fn ensure_winners(
+        &self,
+        raffle_id: RaffleId,
+        config: &Config,
+        tier: &PrizeTier,
+        winners: &[AccountId],
+    ) -> Result<Vec<(TicketId, AccountId)>, RaffleError> {
+        // get the results for the given raffle
+        let results = self
+            .data::<Data>()
+            .results
+            .get(raffle_id)
+            .ok_or(DifferentResults)?;
+
+        let mut winning_tickets = Vec::new();
+        for (i, winner) in winners.iter().enumerate() {
+            // the same account cannot be reported twice in the same tier
+            if winners[..i].contains(winner) {
+                return Err(DuplicatedWinner);
+            }
+
+            let tickets: Vec<TicketId> = (0..self.get_nb_participations_of(*winner, raffle_id))
+                .filter_map(|index| {
+                    self.data::<Data>()
+                        .account_participations
+                        .get((*winner, raffle_id, index))
+                })
+                .filter(|ticket_id| {
+                    self.data::<Data>()
+                        .participations
+                        .get((raffle_id, *ticket_id))
+                        .map(|participation| {
+                            let (nb_matching, nb_matching_bonus) = count_matching_numbers(
+                                config.nb_numbers,
+                                config.draw_mode,
+                                &participation.numbers,
+                                &results,
+                            );
+                            nb_matching == tier.nb_matching_numbers as usize
+                                && nb_matching_bonus == tier.nb_matching_bonus_numbers as usize
+                        })
+                        .unwrap_or(false)
+                })
+                .collect();
+
+            if tickets.is_empty() {
+                return Err(IncorrectWinner);
+            }
+
+            winning_tickets.extend(tickets.into_iter().map(|ticket_id| (ticket_id, *winner)));
+        }
+
+        Ok(winning_tickets)
+    }
+
+    /// save the winners for the current raffle of the game.
     fn set_winners(
         &mut self,
+        game_id: GameId,
         raffle_id: RaffleId,
         winners: Vec<AccountId>,
     ) -> Result<(), RaffleError> {
+        // check if the winners are already saved (ie the request has been answered)
+        if self.data::<Data>().winners.contains(raffle_id) {
+            return Err(ExistingWinners);
+        }
+
         // check the raffle number
-        if self.data::<Data>().current_raffle_id != raffle_id {
+        if self.get_current_raffle_id(game_id) != raffle_id {
             return Err(RaffleError::IncorrectRaffle);
         }
 
         // check the status
-        if self.data::<Data>().status != Status::WaitingWinners {
+        if self.get_current_status(game_id) != Status::WaitingWinners {
             return Err(RaffleError::IncorrectStatus);
         }
 
-        match self.data::<Data>().winners.get(raffle_id) {
-            Some(_) => Err(ExistingWinners),
-            None => {
-                // save the result
-                self.data::<Data>().winners.insert(raffle_id, &winners);
-                // update the status
-                self.data::<Data>().status = Status::Closed;
-                Ok(())
-            }
-        }
+        // save the result
+        self.data::<Data>().winners.insert(raffle_id, &winners);
+        // update the status
+        self.set_status(game_id, Status::Closed);
+        Ok(())
     }
 
-    /// check if the user can participate in the current raffle
-    fn can_participate(&mut self) -> Result<(), RaffleError> {
+    /// check if the user can participate in the current raffle of the game
+    fn can_participate(&mut self, game_id: GameId) -> Result<(), RaffleError> {
         // check the status
-        if self.data::<Data>().status != Status::Ongoing {
+        if self.get_current_status(game_id) != Status::Ongoing {
             return Err(RaffleError::IncorrectStatus);
         }
 
+        // check the end time
+        let raffle_id = self.get_current_raffle_id(game_id);
+        if let Some(end_time) = self.data::<Data>().end_times.get(raffle_id) {
+            if Self::env().block_timestamp() >= end_time {
+                return Err(TooLateToParticipate);
+            }
+        }
+
         Ok(())
     }
+
+    /// check if one more ticket of the account respects the limits of the config
+    fn ensure_participation_limits(
+        &self,
+        game_id: GameId,
+        participant: AccountId,
+        config: &Config,
+    ) -> Result<(), RaffleError> {
+        let raffle_id = self.get_current_raffle_id(game_id);
+
+        if let Some(max_tickets) = config.max_tickets_per_raffle {
+            if self.get_nb_participations(raffle_id) >= max_tickets {
+                return Err(TooManyTicketsForRaffle);
+            }
+        }
+
+        if let Some(max_tickets) = config.max_tickets_per_account {
+            if self.get_nb_participations_of(participant, raffle_id) >= max_tickets {
+                return Err(TooManyTicketsForAccount);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// save the participation in the current raffle of the game and return the ticket id
+    fn add_participation(
+        &mut self,
+        game_id: GameId,
+        participant: AccountId,
+        numbers: Vec<Number>,
+    ) -> Result<TicketId, RaffleError> {
+        let raffle_id = self.get_current_raffle_id(game_id);
+
+        // the ticket id is the index of the participation in the raffle
+        let ticket_id = self
+            .data::<Data>()
+            .nb_participations
+            .get(raffle_id)
+            .unwrap_or_default();
+        let nb_participations = ticket_id.checked_add(1).ok_or(AddOverFlow)?;
+
+        self.data::<Data>().participations.insert(
+            (raffle_id, ticket_id),
+            &Participation {
+                participant,
+                numbers,
+            },
+        );
+        self.data::<Data>()
+            .nb_participations
+            .insert(raffle_id, &nb_participations);
+
+        // index the ticket by account
+        let index = self.get_nb_participations_of(participant, raffle_id);
+        self.data::<Data>()
+            .account_participations
+            .insert((participant, raffle_id, index), &ticket_id);
+        let nb_account_participations = index.checked_add(1).ok_or(AddOverFlow)?;
+        self.data::<Data>()
+            .nb_account_participations
+            .insert((participant, raffle_id), &nb_account_participations);
+
+        Ok(ticket_id)
+    }
+
+    #[ink(message)]
+    fn get_nb_participations(&self, raffle_id: RaffleId) -> u32 {
+        self.data::<Data>()
+            .nb_participations
+            .get(raffle_id)
+            .unwrap_or_default()
+    }
+
+    #[ink(message)]
+    fn get_nb_participations_of(&self, account: AccountId, raffle_id: RaffleId) -> u32 {
+        self.data::<Data>()
+            .nb_account_participations
+            .get((account, raffle_id))
+            .unwrap_or_default()
+    }
+
+    /// return at most `limit` participations of the raffle, starting from the ticket `from`
+    #[ink(message)]
+    fn get_participations(
+        &self,
+        raffle_id: RaffleId,
+        from: TicketId,
+        limit: u32,
+    ) -> Vec<Participation> {
+        let nb_participations = self.get_nb_participations(raffle_id);
+        let to = from.saturating_add(limit).min(nb_participations);
+
+        let mut participations = Vec::new();
+        for ticket_id in from..to {
+            if let Some(participation) = self
+                .data::<Data>()
+                .participations
+                .get((raffle_id, ticket_id))
+            {
+                participations.push(participation);
+            }
+        }
+        participations
+    }
+
+    /// return the participations of the account in the raffle
+    #[ink(message)]
+    fn get_participations_of(&self, account: AccountId, raffle_id: RaffleId) -> Vec<Participation> {
+        (0..self.get_nb_participations_of(account, raffle_id))
+            .filter_map(|index| {
+                self.data::<Data>()
+                    .account_participations
+                    .get((account, raffle_id, index))
+            })
+            .filter_map(|ticket_id| {
+                self.data::<Data>()
+                    .participations
+                    .get((raffle_id, ticket_id))
+            })
+            .collect()
+    }
 }
@@ -1,59 +1,549 @@
+use crate::traits::config::PrizeTier;
 use crate::traits::error::RaffleError;
 use crate::traits::error::RaffleError::*;
+use crate::traits::{GameId, RaffleId, TicketId};
 use ink::prelude::vec::Vec;
+use openbrush::contracts::psp22::PSP22Ref;
 use openbrush::storage::Mapping;
-use openbrush::traits::{AccountId, Balance, Storage};
+use openbrush::traits::{AccountId, Balance, Storage, Timestamp};
 
 #[derive(Default, Debug)]
 #[openbrush::storage_item]
 pub struct Data {
-    pending_rewards: Mapping<AccountId, Balance>,
+    pending_rewards: Mapping<(AccountId, RaffleId), Balance>,
+    rewarded_raffles: Mapping<AccountId, Vec<RaffleId>>,
+    claim_delegates: Mapping<AccountId, AccountId>,
     total_pending_rewards: Balance,
+    unclaimed_rewards: Mapping<RaffleId, Balance>,
+    rewarded_at: Mapping<RaffleId, Timestamp>,
+    claim_deadlines: Mapping<RaffleId, Timestamp>,
+    prize_pools: Mapping<RaffleId, Balance>,
+    rollover_amounts: Mapping<GameId, Balance>,
+    total_rollover_amount: Balance,
+    total_prize_pools: Balance,
+    fee_rate: u16,
+    treasury: Option<AccountId>,
+    total_fees: Balance,
+    ticket_payments: Mapping<(RaffleId, TicketId), Balance>,
+    currency: Currency,
 }
 
+/// currency used to buy the tickets and to pay the rewards
+#[derive(Default, Debug, Eq, PartialEq, Copy, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(
+    feature = "std",
+    derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+)]
+pub enum Currency {
+    /// native balance of the chain
+    #[default]
+    Native,
+    /// PSP22 token deployed at the given address
+    PSP22(AccountId),
+}
+
+/// fee rate expressed in basis points (10_000 = 100%)
+pub const MAX_FEE_RATE: u16 = 10_000;
+
 #[openbrush::trait_definition]
 pub trait RewardManager: Internal + Storage<Data> {
-
+    /// fund the jackpot of the game: the amount is added to the prize pool of its next raffle with winners
     #[ink(message, payable)]
-    fn fund(&mut self) -> Result<(), RaffleError> {
+    fn fund(&mut self, game_id: GameId, amount: Balance) -> Result<(), RaffleError> {
+        self.add_to_rollover_amount(game_id, amount)?;
+        let from = Self::env().caller();
+        self.collect_payment(from, amount)
+    }
+
+    /// set the currency, only possible when no funds are owed in the current currency
+    fn set_currency(&mut self, currency: Currency) -> Result<(), RaffleError> {
+        let data = self.data::<Data>();
+        if data.total_pending_rewards > 0
+            || data.total_fees > 0
+            || data.total_prize_pools > 0
+            || data.total_rollover_amount > 0
+        {
+            return Err(CurrencyInUse);
+        }
+        self.data::<Data>().currency = currency;
         Ok(())
     }
 
-    fn add_winners(&mut self, accounts: Vec<AccountId>) -> Result<(), RaffleError> {
-        let mut total_pending_rewards = self.data::<Data>().total_pending_rewards;
+    #[ink(message)]
+    fn get_currency(&self) -> Currency {
+        self.data::<Data>().currency
+    }
+
+    /// collect the amount paid by the account: the transferred value in native currency
+    /// or a `transfer_from` with the PSP22 token (the contract must be approved by the account)
+    fn collect_payment(&mut self, from: AccountId, amount: Balance) -> Result<(), RaffleError> {
+        let transferred_value = Self::env().transferred_value();
+        match self.data::<Data>().currency {
+            Currency::Native => {
+                if transferred_value != amount {
+                    return Err(IncorrectTransferredValue);
+                }
+            }
+            Currency::PSP22(token) => {
+                if transferred_value != 0 {
+                    return Err(IncorrectTransferredValue);
+                }
+                if amount > 0 {
+                    PSP22Ref::transfer_from(
+                        &token,
+                        from,
+                        Self::env().account_id(),
+                        amount,
+                        Vec::new(),
+                    )
+                    .map_err(|_| TransferError)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// transfer the amount to the account in the currency of the contract
+    fn transfer_to(&mut self, to: AccountId, amount: Balance) -> Result<(), RaffleError> {
+        match self.data::<Data>().currency {
+            Currency::Native => Self::env().transfer(to, amount).map_err(|_| TransferError),
+            Currency::PSP22(token) => {
+                PSP22Ref::transfer(&token, to, amount, Vec::new()).map_err(|_| TransferError)
+            }
+        }
+    }
 
-        let reward = Self::env()
-            .balance()
-            .checked_sub(total_pending_rewards)
-            .ok_or(AddOverFlow)?
-            .checked_div(accounts.len() as u128)
-            .ok_or(DivByZero)?;
-
-        // iterate on the accounts (the winners)
-        for account in accounts {
-            // compute the new rewards for this winner
-            let new_reward = match self.data::<Data>().pending_rewards.get(&account) {
-                Some(existing_reward) => existing_reward.checked_add(reward).ok_or(AddOverFlow)?,
-                _ => reward,
-            };
-
-            // add the pending rewards for this account
+    /// return the balance of the contract in its currency
+    fn get_balance(&self) -> Balance {
+        match self.data::<Data>().currency {
+            Currency::Native => Self::env().balance(),
+            Currency::PSP22(token) => PSP22Ref::balance_of(&token, Self::env().account_id()),
+        }
+    }
+
+    /// add the price paid for the ticket in the prize pool of the raffle
+    fn add_to_prize_pool(
+        &mut self,
+        raffle_id: RaffleId,
+        ticket_id: TicketId,
+        amount: Balance,
+    ) -> Result<(), RaffleError> {
+        // keep the payment of the ticket in case of refund
+        self.data::<Data>()
+            .ticket_payments
+            .insert(&(raffle_id, ticket_id), &amount);
+
+        let prize_pool = self
+            .data::<Data>()
+            .prize_pools
+            .get(&raffle_id)
+            .unwrap_or_default()
+            .checked_add(amount)
+            .ok_or(AddOverFlow)?;
+        self.data::<Data>()
+            .prize_pools
+            .insert(&raffle_id, &prize_pool);
+        self.data::<Data>().total_prize_pools = self
+            .data::<Data>()
+            .total_prize_pools
+            .checked_add(amount)
+            .ok_or(AddOverFlow)?;
+        Ok(())
+    }
+
+    /// return the prize pool of the given raffle: the tickets sold
+    /// and, once the winners are known, the rollover amount distributed with it (0 if rolled over)
+    #[ink(message)]
+    fn get_prize_pool(&self, raffle_id: RaffleId) -> Balance {
+        self.data::<Data>()
+            .prize_pools
+            .get(&raffle_id)
+            .unwrap_or_default()
+    }
+
+    /// return the amount carried over to the next raffle with winners of the game
+    #[ink(message)]
+    fn get_rollover_amount(&self, game_id: GameId) -> Balance {
+        self.data::<Data>()
+            .rollover_amounts
+            .get(&game_id)
+            .unwrap_or_default()
+    }
+
+    /// add the amount to the one carried over to the next raffle with winners of the game
+    fn add_to_rollover_amount(
+        &mut self,
+        game_id: GameId,
+        amount: Balance,
+    ) -> Result<(), RaffleError> {
+        let rollover_amount = self
+            .get_rollover_amount(game_id)
+            .checked_add(amount)
+            .ok_or(AddOverFlow)?;
+        self.data::<Data>()
+            .rollover_amounts
+            .insert(&game_id, &rollover_amount);
+        self.data::<Data>().total_rollover_amount = self
+            .data::<Data>()
+            .total_rollover_amount
+            .checked_add(amount)
+            .ok_or(AddOverFlow)?;
+        Ok(())
+    }
+
+    /// take the amount carried over to the next raffle with winners of the game
+    fn take_rollover_amount(&mut self, game_id: GameId) -> Result<Balance, RaffleError> {
+        let rollover_amount = self.get_rollover_amount(game_id);
+        self.data::<Data>().rollover_amounts.remove(&game_id);
+        self.data::<Data>().total_rollover_amount = self
+            .data::<Data>()
+            .total_rollover_amount
+            .checked_sub(rollover_amount)
+            .ok_or(SubOverFlow)?;
+        Ok(rollover_amount)
+    }
+
+    /// roll the prize pool of the raffle without winner into the next raffle of the game
+    fn roll_over_prize_pool(
+        &mut self,
+        game_id: GameId,
+        raffle_id: RaffleId,
+    ) -> Result<(), RaffleError> {
+        let prize_pool = self.get_prize_pool(raffle_id);
+        self.add_to_rollover_amount(game_id, prize_pool)?;
+        // the prize pool is moved: it is not owed by this raffle anymore
+        self.data::<Data>().prize_pools.remove(&raffle_id);
+        self.data::<Data>().total_prize_pools = self
+            .data::<Data>()
+            .total_prize_pools
+            .checked_sub(prize_pool)
+            .ok_or(SubOverFlow)?;
+        self.emit_jackpot_rolled_over_event(raffle_id, prize_pool);
+        Ok(())
+    }
+
+    /// return the price paid for the ticket in the raffle (0 if it is refunded)
+    #[ink(message)]
+    fn get_ticket_payment(&self, raffle_id: RaffleId, ticket_id: TicketId) -> Balance {
+        self.data::<Data>()
+            .ticket_payments
+            .get(&(raffle_id, ticket_id))
+            .unwrap_or_default()
+    }
+
+    /// refund the price paid for the tickets to their current owner
+    fn refund(
+        &mut self,
+        raffle_id: RaffleId,
+        ticket_ids: &[TicketId],
+        owner: AccountId,
+    ) -> Result<(), RaffleError> {
+        let mut amount: Balance = 0;
+        for ticket_id in ticket_ids {
+            let ticket_payment = self
+                .data::<Data>()
+                .ticket_payments
+                .get(&(raffle_id, *ticket_id))
+                .filter(|amount| *amount > 0)
+                .ok_or(NoRefund)?;
+            amount = amount.checked_add(ticket_payment).ok_or(AddOverFlow)?;
+            // a ticket is refunded only once
             self.data::<Data>()
-                .pending_rewards
-                .insert(&account, &new_reward);
+                .ticket_payments
+                .remove(&(raffle_id, *ticket_id));
+        }
+        if amount == 0 {
+            return Err(NoRefund);
+        }
+
+        // the refund is taken from the prize pool of the raffle
+        let prize_pool = self
+            .get_prize_pool(raffle_id)
+            .checked_sub(amount)
+            .ok_or(SubOverFlow)?;
+        self.data::<Data>()
+            .prize_pools
+            .insert(&raffle_id, &prize_pool);
+        self.data::<Data>().total_prize_pools = self
+            .data::<Data>()
+            .total_prize_pools
+            .checked_sub(amount)
+            .ok_or(SubOverFlow)?;
+
+        // transfer the amount
+        self.transfer_to(owner, amount)?;
+        // emmit the event
+        self.emit_refund_claimed_event(raffle_id, owner, amount);
+
+        Ok(())
+    }
+
+    /// share the prize pool between the prize tiers and the prize of each tier between its winning tickets.
+    /// The rewards must be claimed before the end of the claim duration (if any)
+    fn add_winners(
+        &mut self,
+        game_id: GameId,
+        raffle_id: RaffleId,
+        tiers: Vec<(PrizeTier, Vec<AccountId>)>,
+        claim_duration: Option<Timestamp>,
+    ) -> Result<(), RaffleError> {
+        let mut total_pending_rewards = self.data::<Data>().total_pending_rewards;
+        let mut raffle_rewards: Balance = 0;
+
+        // the prize pool of this raffle is not live anymore
+        let tickets_sold = self.get_prize_pool(raffle_id);
+        self.data::<Data>().total_prize_pools = self
+            .data::<Data>()
+            .total_prize_pools
+            .checked_sub(tickets_sold)
+            .ok_or(SubOverFlow)?;
+
+        // the rollover amount of the game is distributed with the prize pool of this raffle
+        let prize_pool = tickets_sold
+            .checked_add(self.take_rollover_amount(game_id)?)
+            .ok_or(AddOverFlow)?;
+        self.data::<Data>()
+            .prize_pools
+            .insert(&raffle_id, &prize_pool);
+
+        let fee_rate = self.data::<Data>().fee_rate;
+        let mut total_fees = self.data::<Data>().total_fees;
+        let mut remaining_prize_pool = prize_pool;
+
+        // iterate on the tiers
+        for (tier, accounts) in tiers {
+            // no winner for this tier
+            if accounts.is_empty() {
+                continue;
+            }
 
-            self.emit_pending_reward_event(account, reward);
+            let tier_prize = prize_pool
+                .checked_mul(tier.share as Balance)
+                .ok_or(MulOverFlow)?
+                .checked_div(100)
+                .ok_or(DivByZero)?;
 
-            // update the total pending rewards
-            total_pending_rewards = total_pending_rewards
-                .checked_add(reward)
-                .ok_or(AddOverFlow)?;
+            // the protocol fee is taken on the distributed prize
+            let fees = tier_prize
+                .checked_mul(fee_rate as Balance)
+                .ok_or(MulOverFlow)?
+                .checked_div(MAX_FEE_RATE as Balance)
+                .ok_or(DivByZero)?;
+            total_fees = total_fees.checked_add(fees).ok_or(AddOverFlow)?;
+            remaining_prize_pool = remaining_prize_pool.checked_sub(fees).ok_or(SubOverFlow)?;
+
+            let reward = tier_prize
+                .checked_sub(fees)
+                .ok_or(SubOverFlow)?
+                .checked_div(accounts.len() as Balance)
+                .ok_or(DivByZero)?;
+
+            // iterate on the accounts (the owner of each winning ticket)
+            for account in accounts.iter() {
+                // add the pending rewards for this account
+                self.add_pending_reward(*account, raffle_id, reward)?;
+
+                self.emit_pending_reward_event(*account, raffle_id, reward);
+
+                // update the total pending rewards
+                total_pending_rewards = total_pending_rewards
+                    .checked_add(reward)
+                    .ok_or(AddOverFlow)?;
+                raffle_rewards = raffle_rewards.checked_add(reward).ok_or(AddOverFlow)?;
+
+                remaining_prize_pool = remaining_prize_pool
+                    .checked_sub(reward)
+                    .ok_or(SubOverFlow)?;
+            }
+
+            self.emit_prize_tier_rewarded_event(
+                raffle_id,
+                tier.nb_matching_numbers,
+                tier.nb_matching_bonus_numbers,
+                accounts,
+                reward,
+            );
         }
         // update the storage
         self.data::<Data>().total_pending_rewards = total_pending_rewards;
+        self.data::<Data>().total_fees = total_fees;
+        self.data::<Data>()
+            .unclaimed_rewards
+            .insert(&raffle_id, &raffle_rewards);
+
+        // save when the rewards became pending and until when they can be claimed
+        let now = Self::env().block_timestamp();
+        self.data::<Data>().rewarded_at.insert(&raffle_id, &now);
+        if let Some(claim_duration) = claim_duration {
+            let claim_deadline = now.checked_add(claim_duration).ok_or(AddOverFlow)?;
+            self.data::<Data>()
+                .claim_deadlines
+                .insert(&raffle_id, &claim_deadline);
+        }
+
+        // the shares of the tiers without winner are rolled over to the next raffle
+        if remaining_prize_pool > 0 {
+            self.add_to_rollover_amount(game_id, remaining_prize_pool)?;
+            self.emit_jackpot_rolled_over_event(raffle_id, remaining_prize_pool);
+        }
+        Ok(())
+    }
+
+    /// add the reward won in the raffle to the pending rewards of the account
+    fn add_pending_reward(
+        &mut self,
+        account: AccountId,
+        raffle_id: RaffleId,
+        reward: Balance,
+    ) -> Result<(), RaffleError> {
+        // the same account can win in several tiers of the raffle
+        let pending_reward = match self
+            .data::<Data>()
+            .pending_rewards
+            .get(&(account, raffle_id))
+        {
+            Some(existing_reward) => existing_reward.checked_add(reward).ok_or(AddOverFlow)?,
+            None => {
+                // index the raffle for this account
+                let mut raffle_ids = self
+                    .data::<Data>()
+                    .rewarded_raffles
+                    .get(&account)
+                    .unwrap_or_default();
+                raffle_ids.push(raffle_id);
+                self.data::<Data>()
+                    .rewarded_raffles
+                    .insert(&account, &raffle_ids);
+                reward
+            }
+        };
+        self.data::<Data>()
+            .pending_rewards
+            .insert(&(account, raffle_id), &pending_reward);
         Ok(())
     }
 
+    /// return when the rewards of the raffle became pending
+    #[ink(message)]
+    fn get_rewarded_at(&self, raffle_id: RaffleId) -> Option<Timestamp> {
+        self.data::<Data>().rewarded_at.get(&raffle_id)
+    }
+
+    /// return the time after which the rewards of the raffle cannot be claimed anymore
+    #[ink(message)]
+    fn get_claim_deadline(&self, raffle_id: RaffleId) -> Option<Timestamp> {
+        self.data::<Data>().claim_deadlines.get(&raffle_id)
+    }
+
+    /// return the rewards of the raffle not claimed yet
+    #[ink(message)]
+    fn get_unclaimed_rewards(&self, raffle_id: RaffleId) -> Balance {
+        self.data::<Data>()
+            .unclaimed_rewards
+            .get(&raffle_id)
+            .unwrap_or_default()
+    }
+
+    /// return true if the claim deadline of the raffle is passed
+    fn is_reward_expired(&self, raffle_id: RaffleId) -> bool {
+        match self.data::<Data>().claim_deadlines.get(&raffle_id) {
+            Some(deadline) => Self::env().block_timestamp() >= deadline,
+            None => false,
+        }
+    }
+
+    /// move the expired rewards of the raffle into the prize pool of the next raffle with winners of the game
+    fn sweep_expired_rewards(
+        &mut self,
+        game_id: GameId,
+        raffle_id: RaffleId,
+    ) -> Result<Balance, RaffleError> {
+        if !self.is_reward_expired(raffle_id) {
+            return Err(RewardNotExpired);
+        }
+
+        let amount = self
+            .data::<Data>()
+            .unclaimed_rewards
+            .get(&raffle_id)
+            .filter(|amount| *amount > 0)
+            .ok_or(NoReward)?;
+        self.data::<Data>().unclaimed_rewards.remove(&raffle_id);
+        self.data::<Data>().total_pending_rewards = self
+            .data::<Data>()
+            .total_pending_rewards
+            .checked_sub(amount)
+            .ok_or(SubOverFlow)?;
+        self.add_to_rollover_amount(game_id, amount)?;
+
+        // emmit the event
+        self.emit_expired_rewards_swept_event(raffle_id, amount);
+
+        Ok(amount)
+    }
+
+    /// set the protocol fee rate (in basis points) and the treasury account receiving the fees
+    fn set_fee(&mut self, fee_rate: u16, treasury: AccountId) -> Result<(), RaffleError> {
+        if fee_rate > MAX_FEE_RATE {
+            return Err(IncorrectFeeRate);
+        }
+        self.data::<Data>().fee_rate = fee_rate;
+        self.data::<Data>().treasury = Some(treasury);
+        Ok(())
+    }
+
+    #[ink(message)]
+    fn get_fee_rate(&self) -> u16 {
+        self.data::<Data>().fee_rate
+    }
+
+    #[ink(message)]
+    fn get_treasury(&self) -> Option<AccountId> {
+        self.data::<Data>().treasury
+    }
+
+    /// return the fees collected and not withdrawn yet
+    #[ink(message)]
+    fn get_total_fees(&self) -> Balance {
+        self.data::<Data>().total_fees
+    }
+
+    /// transfer the collected fees to the treasury (only the treasury can call this method)
+    #[ink(message)]
+    fn withdraw_fees(&mut self) -> Result<(), RaffleError> {
+        let treasury = self.data::<Data>().treasury.ok_or(TreasuryNotSet)?;
+        if Self::env().caller() != treasury {
+            return Err(NotTreasury);
+        }
+
+        let total_fees = self.data::<Data>().total_fees;
+        if total_fees == 0 {
+            return Err(NoFees);
+        }
+
+        // transfer the amount
+        self.transfer_to(treasury, total_fees)?;
+        // emmit the event
+        self.emit_fees_withdrawn_event(treasury, total_fees);
+        // reset the fees
+        self.data::<Data>().total_fees = 0;
+
+        Ok(())
+    }
+
+    /// return the part of the contract balance owed to nobody:
+    /// ie without the pending rewards, the fees and the live prize pools
+    #[ink(message)]
+    fn get_available_balance(&self) -> Balance {
+        let data = self.data::<Data>();
+        let locked_balance = data
+            .total_pending_rewards
+            .saturating_add(data.total_fees)
+            .saturating_add(data.total_prize_pools)
+            .saturating_add(data.total_rollover_amount);
+        self.get_balance().saturating_sub(locked_balance)
+    }
+
     /// return the total pending reward
     #[ink(message)]
     fn get_total_pending_rewards(&mut self) -> Balance {
@@ -74,7 +564,7 @@ pub trait RewardManager: Internal + Storage<Data> {
     }
 
     fn inner_has_pending_rewards_from(&self, from: AccountId) -> bool {
-        self.data::<Data>().pending_rewards.contains(&from)
+        self.inner_get_pending_rewards_from(from).is_some()
     }
 
     /// return the pending rewards for a given account.
@@ -83,53 +573,191 @@ pub trait RewardManager: Internal + Storage<Data> {
         &mut self,
         from: AccountId,
     ) -> Option<Balance> {
-        self.data::<Data>().pending_rewards.get(&from)
+        self.inner_get_pending_rewards_from(from)
     }
 
-    /// claim all pending rewards for the current account
-    /// After claiming, there is not anymore pending rewards for this account
+    /// return the pending rewards of the account for each raffle, without the expired ones
     #[ink(message)]
-    fn claim(&mut self) -> Result<(), RaffleError> {
+    fn get_pending_rewards_detail(&self, account: AccountId) -> Vec<(RaffleId, Balance)> {
+        self.data::<Data>()
+            .rewarded_raffles
+            .get(&account)
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|raffle_id| !self.is_reward_expired(*raffle_id))
+            .filter_map(|raffle_id| {
+                self.data::<Data>()
+                    .pending_rewards
+                    .get(&(account, raffle_id))
+                    .map(|amount| (raffle_id, amount))
+            })
+            .collect()
+    }
+
+    /// return the pending rewards of the account, without the expired ones
+    fn inner_get_pending_rewards_from(&self, from: AccountId) -> Option<Balance> {
+        let rewards = self.get_pending_rewards_detail(from);
+        if rewards.is_empty() {
+            return None;
+        }
+        Some(rewards.iter().map(|(_, amount)| amount).sum())
+    }
+
+    /// claim the pending rewards for the current account: all of them or only the ones of the given raffle.
+    /// After claiming, there is not anymore pending rewards for this account (or this raffle)
+    #[ink(message)]
+    fn claim(&mut self, raffle_id: Option<RaffleId>) -> Result<(), RaffleError> {
         let from = Self::env().caller();
-        self.inner_claim_from(from)
+        self.inner_claim(from, from, raffle_id)
     }
 
-    /// claim all pending rewards for the given account
-    /// After claiming, there is not anymore pending rewards for this account
+    /// claim the pending rewards for the current account and transfer them to the beneficiary
     #[ink(message)]
-    fn claim_from(&mut self, from: AccountId) -> Result<(), RaffleError> {
-        self.inner_claim_from(from)
+    fn claim_to(
+        &mut self,
+        beneficiary: AccountId,
+        raffle_id: Option<RaffleId>,
+    ) -> Result<(), RaffleError> {
+        let from = Self::env().caller();
+        self.inner_claim(from, beneficiary, raffle_id)
     }
 
-    fn inner_claim_from(&mut self, from: AccountId) -> Result<(), RaffleError> {
-        // get all pending rewards for this account
-        match self.data::<Data>().pending_rewards.get(&from) {
-            Some(pending_rewards) => {
-                // transfer the amount
-                Self::env()
-                    .transfer(from, pending_rewards)
-                    .map_err(|_| TransferError)?;
-                // emmit the event
-                self.emit_rewards_claimed_event(from, pending_rewards);
-                // remove the pending rewards
-                self.data::<Data>().pending_rewards.remove(&from);
+    /// claim the pending rewards for the given account: all of them or only the ones of the given raffle.
+    /// Only the delegate registered by the account can claim on its behalf and the rewards go to the account
+    #[ink(message)]
+    fn claim_from(
+        &mut self,
+        from: AccountId,
+        raffle_id: Option<RaffleId>,
+    ) -> Result<(), RaffleError> {
+        let caller = Self::env().caller();
+        if caller != from && self.get_claim_delegate(from) != Some(caller) {
+            return Err(ClaimNotAllowed);
+        }
+        self.inner_claim(from, from, raffle_id)
+    }
 
-                // update the total pending rewards
-                self.data::<Data>().total_pending_rewards = self
-                    .data::<Data>()
-                    .total_pending_rewards
-                    .checked_sub(pending_rewards)
-                    .ok_or(SubOverFlow)?;
+    /// register the account allowed to claim the rewards on behalf of the caller (None to remove it)
+    #[ink(message)]
+    fn set_claim_delegate(&mut self, delegate: Option<AccountId>) -> Result<(), RaffleError> {
+        let caller = Self::env().caller();
+        match delegate {
+            Some(delegate) => {
+                self.data::<Data>()
+                    .claim_delegates
+                    .insert(&caller, &delegate);
+            }
+            None => self.data::<Data>().claim_delegates.remove(&caller),
+        }
+        Ok(())
+    }
 
-                Ok(())
+    #[ink(message)]
+    fn get_claim_delegate(&self, account: AccountId) -> Option<AccountId> {
+        self.data::<Data>().claim_delegates.get(&account)
+    }
+
+    /// claim the pending rewards of the account and transfer them to the beneficiary
+    fn inner_claim(
+        &mut self,
+        from: AccountId,
+        beneficiary: AccountId,
+        raffle_id: Option<RaffleId>,
+    ) -> Result<(), RaffleError> {
+        // check if the claims are not paused
+        self.ensure_claims_allowed()?;
+
+        // get the raffles with pending rewards for this account
+        let mut raffle_ids = self
+            .data::<Data>()
+            .rewarded_raffles
+            .get(&from)
+            .unwrap_or_default();
+        let claimed_raffle_ids: Vec<RaffleId> = match raffle_id {
+            Some(raffle_id) => raffle_ids
+                .iter()
+                .copied()
+                .filter(|r| *r == raffle_id)
+                .collect(),
+            None => raffle_ids.clone(),
+        };
+        if claimed_raffle_ids.is_empty() {
+            return Err(NoReward);
+        }
+
+        // the expired rewards are dropped, they are swept by the raffle operator
+        let mut claimed_rewards = Vec::new();
+        let mut total_claimed: Balance = 0;
+        for raffle_id in claimed_raffle_ids.iter().copied() {
+            let amount = self
+                .data::<Data>()
+                .pending_rewards
+                .get(&(from, raffle_id))
+                .unwrap_or_default();
+            self.data::<Data>()
+                .pending_rewards
+                .remove(&(from, raffle_id));
+            if self.is_reward_expired(raffle_id) {
+                continue;
             }
-            _ => Err(NoReward),
+            let unclaimed_rewards = self
+                .get_unclaimed_rewards(raffle_id)
+                .checked_sub(amount)
+                .ok_or(SubOverFlow)?;
+            self.data::<Data>()
+                .unclaimed_rewards
+                .insert(&raffle_id, &unclaimed_rewards);
+            total_claimed = total_claimed.checked_add(amount).ok_or(AddOverFlow)?;
+            claimed_rewards.push((raffle_id, amount));
+        }
+        if claimed_rewards.is_empty() {
+            return Err(RewardExpired);
+        }
+
+        // remove the claimed raffles from the index
+        raffle_ids.retain(|r| !claimed_raffle_ids.contains(r));
+        if raffle_ids.is_empty() {
+            self.data::<Data>().rewarded_raffles.remove(&from);
+        } else {
+            self.data::<Data>()
+                .rewarded_raffles
+                .insert(&from, &raffle_ids);
         }
+
+        // update the total pending rewards
+        self.data::<Data>().total_pending_rewards = self
+            .data::<Data>()
+            .total_pending_rewards
+            .checked_sub(total_claimed)
+            .ok_or(SubOverFlow)?;
+
+        // transfer the amount
+        self.transfer_to(beneficiary, total_claimed)?;
+        // emmit the events
+        for (raffle_id, amount) in claimed_rewards {
+            self.emit_rewards_claimed_event(from, raffle_id, amount);
+        }
+
+        Ok(())
     }
 }
 
 #[openbrush::trait_definition]
 pub trait Internal {
-    fn emit_pending_reward_event(&self, account: AccountId, amount: Balance);
-    fn emit_rewards_claimed_event(&self, account: AccountId, amount: Balance);
+    /// throw an error if the claims are paused
+    fn ensure_claims_allowed(&self) -> Result<(), RaffleError>;
+    fn emit_pending_reward_event(&self, account: AccountId, raffle_id: RaffleId, amount: Balance);
+    fn emit_rewards_claimed_event(&self, account: AccountId, raffle_id: RaffleId, amount: Balance);
+    fn emit_jackpot_rolled_over_event(&self, raffle_id: RaffleId, amount: Balance);
+    fn emit_fees_withdrawn_event(&self, treasury: AccountId, amount: Balance);
+    fn emit_refund_claimed_event(&self, raffle_id: RaffleId, account: AccountId, amount: Balance);
+    fn emit_expired_rewards_swept_event(&self, raffle_id: RaffleId, amount: Balance);
+    fn emit_prize_tier_rewarded_event(
+        &self,
+        raffle_id: RaffleId,
+        nb_matching_numbers: u8,
+        nb_matching_bonus_numbers: u8,
+        winners: Vec<AccountId>,
+        reward: Balance,
+    );
 }
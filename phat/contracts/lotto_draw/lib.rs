@@ -15,9 +15,17 @@ mod lotto_draw {
     use serde_json_core;
     use sp_core::crypto::{AccountId32, Ss58Codec};
 
+    pub type GameId = u32;
     pub type RaffleId = u32;
     pub type Number = u16;
 
+    /// suffix of the salt used to draw the bonus numbers
+    const BONUS_SALT: &[u8] = b"bonus";
+    /// suffix of the salt used to draw the seed of the quick-pick tickets
+    const QUICK_PICK_SALT: &[u8] = b"quick_pick";
+    /// number of participations fetched per query to the indexer (SubQuery returns 100 nodes by default)
+    const PARTICIPATIONS_PAGE_SIZE: usize = 100;
+
     /// Message to request the lotto lotto_draw or the list of winners
     /// message pushed in the queue by the Ink! smart contract and read by the offchain rollup
     #[derive(Eq, PartialEq, Clone, Debug, scale::Encode, scale::Decode)]
@@ -26,6 +34,8 @@ mod lotto_draw {
         derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
     )]
     pub struct LottoRequestMessage {
+        /// game id
+        game_id: GameId,
         /// lotto_draw number
         raffle_id: RaffleId,
         /// request
@@ -39,12 +49,63 @@ mod lotto_draw {
     )]
     pub enum Request {
         /// request to lotto_draw the n number between min and max values
-        /// arg1: number of numbers for the lotto_draw
-        /// arg2:  smallest number for the lotto_draw
-        /// arg2:  biggest number for the lotto_draw
-        DrawNumbers(u8, Number, Number),
+        DrawNumbers(DrawParams),
         /// request to check if there is a winner for the given numbers
         CheckWinners(Vec<Number>),
+        /// request to check the winners of each prize tier for the given numbers
+        /// arg1: winning numbers followed by the winning bonus numbers
+        /// arg2: number of winning numbers (without the bonus numbers)
+        /// arg3: number of matching numbers and bonus numbers for each prize tier
+        /// arg4: draw mode used to match the numbers
+        CheckTieredWinners(Vec<Number>, u8, Vec<(u8, u8)>, DrawMode),
+        /// request to draw the seed used to generate the quick-pick tickets of the raffle
+        DrawQuickPickSeed,
+    }
+
+    /// parameters of the lotto_draw
+    /// (same encoding as the arguments of the `DrawNumbers` request sent by the Ink! smart contract)
+    #[derive(Eq, PartialEq, Clone, Debug, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct DrawParams {
+        /// number of numbers for the lotto_draw
+        pub nb_numbers: u8,
+        /// smallest number for the lotto_draw
+        pub smallest_number: Number,
+        /// biggest number for the lotto_draw
+        pub biggest_number: Number,
+        /// number of bonus numbers, smallest and biggest bonus numbers (if any)
+        pub bonus: Option<(u8, Number, Number)>,
+        /// draw mode (distinct unordered numbers or ordered numbers with repetition)
+        pub draw_mode: DrawMode,
+    }
+
+    #[derive(Default, Eq, PartialEq, Copy, Clone, Debug, scale::Encode, scale::Decode)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum DrawMode {
+        /// distinct numbers, the order does not matter
+        #[default]
+        Unordered,
+        /// numbers can be repeated and must match at the same position (ie pick-3 / pick-4 games)
+        Ordered,
+    }
+
+    /// return the number of numbers matching the results, according to the draw mode
+    fn count_matching(draw_mode: DrawMode, numbers: &[Number], results: &[Number]) -> usize {
+        match draw_mode {
+            DrawMode::Unordered => numbers.iter().filter(|n| results.contains(n)).count(),
+            // in ordered mode, the numbers are compared position by position
+            DrawMode::Ordered => numbers
+                .iter()
+                .zip(results.iter())
+                .filter(|(n, r)| n == r)
+                .count(),
+        }
     }
 
     /// Message sent to provide the lotto lotto_draw or the list of winners
@@ -67,6 +128,10 @@ mod lotto_draw {
         Numbers(Vec<Number>),
         /// list of winners
         Winners(Vec<AccountId>),
+        /// list of winners for each number of matching numbers and bonus numbers
+        TieredWinners(Vec<(u8, u8, Vec<AccountId>)>),
+        /// seed used to generate the quick-pick tickets
+        QuickPickSeed([u8; 32]),
     }
 
     /// DTO use for serializing and deserializing the json
@@ -93,6 +158,8 @@ mod lotto_draw {
     #[allow(non_snake_case)]
     struct ParticipationNode<'a> {
         accountId: &'a str,
+        #[serde(default)]
+        numbers: Vec<Number>,
     }
 
     #[ink(storage)]
@@ -288,17 +355,29 @@ mod lotto_draw {
 
         fn handle_request(&self, message: LottoRequestMessage) -> Result<LottoResponseMessage> {
             let response = match message.request {
-                Request::DrawNumbers(nb_numbers, smallest_number, biggest_number) => self
-                    .inner_get_numbers(
-                        message.raffle_id,
-                        nb_numbers,
-                        smallest_number,
-                        biggest_number,
-                    )
+                Request::DrawNumbers(ref draw_params) => self
+                    .inner_get_numbers_with_bonus(message.game_id, message.raffle_id, draw_params)
                     .map(Response::Numbers)?,
                 Request::CheckWinners(ref numbers) => self
                     .inner_get_winners(message.raffle_id, numbers)
                     .map(Response::Winners)?,
+                Request::CheckTieredWinners(
+                    ref numbers,
+                    nb_numbers,
+                    ref nb_matching_numbers,
+                    draw_mode,
+                ) => self
+                    .inner_get_tiered_winners(
+                        message.raffle_id,
+                        numbers,
+                        nb_numbers,
+                        nb_matching_numbers,
+                        draw_mode,
+                    )
+                    .map(Response::TieredWinners)?,
+                Request::DrawQuickPickSeed => self
+                    .inner_draw_quick_pick_seed(message.game_id, message.raffle_id)
+                    .map(Response::QuickPickSeed)?,
             };
 
             Ok(LottoResponseMessage {
@@ -312,10 +391,9 @@ mod lotto_draw {
         pub fn verify_numbers(
             &self,
             contract_id: ContractId,
+            game_id: GameId,
             raffle_id: RaffleId,
-            nb_numbers: u8,
-            smallest_number: Number,
-            biggest_number: Number,
+            draw_params: DrawParams,
             numbers: Vec<Number>,
         ) -> Result<bool> {
             let config = self.ensure_client_configured()?;
@@ -330,56 +408,112 @@ mod lotto_draw {
             const LAST_RAFFLE_FOR_VERIF: u32 = ink::selector_id!("LAST_RAFFLE_FOR_VERIF");
 
             let last_raffle: RaffleId = client
-                .get(&LAST_RAFFLE_FOR_VERIF)
+                .get(&(LAST_RAFFLE_FOR_VERIF, game_id))
                 .log_err("verify numbers: last raffle unknown")?
                 .ok_or(ContractError::CurrentRaffleUnknown)?;
 
-            // verify the winning numbers only for the past raffles
+            // verify the winning numbers only for the past raffles of the game
             if raffle_id > last_raffle {
                 return Err(ContractError::UnauthorizedRaffle);
             }
 
-            self.inner_verify_numbers(
-                raffle_id,
-                nb_numbers,
-                smallest_number,
-                biggest_number,
-                numbers,
-            )
+            self.inner_verify_numbers(game_id, raffle_id, &draw_params, numbers)
         }
 
         pub fn inner_verify_numbers(
             &self,
+            game_id: GameId,
             raffle_id: RaffleId,
-            nb_numbers: u8,
-            smallest_number: Number,
-            biggest_number: Number,
+            draw_params: &DrawParams,
             numbers: Vec<Number>,
         ) -> Result<bool> {
             let winning_numbers =
-                self.inner_get_numbers(raffle_id, nb_numbers, smallest_number, biggest_number)?;
+                self.inner_get_numbers_with_bonus(game_id, raffle_id, draw_params)?;
             if winning_numbers.len() != numbers.len() {
                 return Ok(false);
             }
 
-            for n in &numbers {
+            // in ordered mode, the numbers must be in the same order
+            if draw_params.draw_mode == DrawMode::Ordered {
+                return Ok(winning_numbers == numbers);
+            }
+
+            // the numbers and the bonus numbers are compared separately
+            let (winning_numbers, winning_bonus_numbers) =
+                winning_numbers.split_at(draw_params.nb_numbers as usize);
+            let (numbers, bonus_numbers) = numbers.split_at(draw_params.nb_numbers as usize);
+
+            for n in numbers {
                 if !winning_numbers.contains(n) {
                     return Ok(false);
                 }
             }
 
+            for n in bonus_numbers {
+                if !winning_bonus_numbers.contains(n) {
+                    return Ok(false);
+                }
+            }
+
             Ok(true)
         }
 
+        /// draw the numbers followed by the bonus numbers (if any)
+        fn inner_get_numbers_with_bonus(
+            &self,
+            game_id: GameId,
+            raffle_id: RaffleId,
+            draw_params: &DrawParams,
+        ) -> Result<Vec<Number>> {
+            let mut numbers = self.inner_get_numbers(game_id, raffle_id, draw_params)?;
+
+            if let Some((nb_bonus_numbers, smallest_bonus_number, biggest_bonus_number)) =
+                draw_params.bonus
+            {
+                let bonus_params = DrawParams {
+                    nb_numbers: nb_bonus_numbers,
+                    smallest_number: smallest_bonus_number,
+                    biggest_number: biggest_bonus_number,
+                    bonus: None,
+                    draw_mode: draw_params.draw_mode,
+                };
+                let bonus_numbers =
+                    self.inner_draw_numbers(game_id, raffle_id, BONUS_SALT, &bonus_params)?;
+                numbers.extend_from_slice(&bonus_numbers);
+            }
+
+            Ok(numbers)
+        }
+
+        /// draw the numbers without the bonus numbers
         fn inner_get_numbers(
             &self,
+            game_id: GameId,
             raffle_id: RaffleId,
-            nb_numbers: u8,
-            smallest_number: Number,
-            biggest_number: Number,
+            draw_params: &DrawParams,
         ) -> Result<Vec<Number>> {
+            self.inner_draw_numbers(game_id, raffle_id, &[], draw_params)
+        }
+
+        /// draw the numbers (distinct in unordered mode),
+        /// the salt suffix is used to draw different pools for the same raffle
+        fn inner_draw_numbers(
+            &self,
+            game_id: GameId,
+            raffle_id: RaffleId,
+            salt_suffix: &[u8],
+            draw_params: &DrawParams,
+        ) -> Result<Vec<Number>> {
+            let DrawParams {
+                nb_numbers,
+                smallest_number,
+                biggest_number,
+                draw_mode,
+                ..
+            } = *draw_params;
+
             info!(
-                "Request received for raffle {raffle_id} - draw {nb_numbers} numbers between {smallest_number} and {biggest_number}"
+                "Request received for game {game_id} and raffle {raffle_id} - draw {nb_numbers} numbers between {smallest_number} and {biggest_number}"
             );
 
             let contract_id = self.ensure_client_configured()?.contract_id;
@@ -396,12 +530,14 @@ mod lotto_draw {
                 let mut salt: Vec<u8> = Vec::new();
                 salt.extend_from_slice(&i.to_be_bytes());
                 salt.extend_from_slice(&raffle_id.to_be_bytes());
+                salt.extend_from_slice(&game_id.to_be_bytes());
                 salt.extend_from_slice(&contract_id);
+                salt.extend_from_slice(salt_suffix);
 
                 // lotto_draw the number
                 let number = self.inner_get_number(salt, smallest_number, biggest_number)?;
-                // check if the number has already been drawn
-                if !numbers.iter().any(|&n| n == number) {
+                // check if the number has already been drawn (allowed in ordered mode)
+                if draw_mode == DrawMode::Ordered || !numbers.iter().any(|&n| n == number) {
                     // the number has not been drawn yet => we added it
                     numbers.push(number);
                 }
@@ -414,6 +550,31 @@ mod lotto_draw {
             Ok(numbers)
         }
 
+        /// draw the seed used by the Ink! smart contract to generate the quick-pick tickets
+        fn inner_draw_quick_pick_seed(
+            &self,
+            game_id: GameId,
+            raffle_id: RaffleId,
+        ) -> Result<[u8; 32]> {
+            info!("Request received for game {game_id} and raffle {raffle_id} - draw the quick-pick seed");
+
+            let contract_id = self.ensure_client_configured()?.contract_id;
+
+            // build a salt for this seed
+            let mut salt: Vec<u8> = Vec::new();
+            salt.extend_from_slice(&raffle_id.to_be_bytes());
+            salt.extend_from_slice(&game_id.to_be_bytes());
+            salt.extend_from_slice(&contract_id);
+            salt.extend_from_slice(QUICK_PICK_SALT);
+
+            let output = vrf(&salt);
+            // keep only 32 bytes for the seed
+            let mut seed = [0x00; 32];
+            seed.copy_from_slice(&output[0..32]);
+
+            Ok(seed)
+        }
+
         fn inner_get_number(&self, salt: Vec<u8>, min: Number, max: Number) -> Result<Number> {
             let output = vrf(&salt);
             // keep only 8 bytes to compute the random u64
@@ -440,7 +601,7 @@ mod lotto_draw {
         fn inner_get_winners(
             &self,
             raffle_id: RaffleId,
-            numbers: &Vec<Number>,
+            numbers: &[Number],
         ) -> Result<Vec<AccountId>> {
             info!(
                 "Request received to get the winners for raffle id {raffle_id} and numbers {numbers:?} "
@@ -450,14 +611,6 @@ mod lotto_draw {
                 return Err(ContractError::NoNumber);
             }
 
-            // check if the endpoint is configured
-            let indexer_endpoint = self.ensure_indexer_configured()?;
-
-            // build the headers
-            let headers = alloc::vec![
-                ("Content-Type".into(), "application/json".into()),
-                ("Accept".into(), "application/json".into())
-            ];
             // build the filter
             let mut filter = format!(
                 r#"filter:{{and:[{{numRaffle:{{equalTo:\"{}\"}}}}"#,
@@ -469,43 +622,129 @@ mod lotto_draw {
             }
             filter.push_str("]}");
 
-            // build the body
-            let body = format!(
-                r#"{{"query" : "{{participations({}){{ nodes {{ accountId }} }} }}"}}"#,
-                filter
-            );
+            // add the winners (an account with several winning tickets is added once)
+            let mut winners = Vec::new();
+            self.query_participations(&filter, "accountId", |account_id, _| {
+                if !winners.contains(&account_id) {
+                    winners.push(account_id);
+                }
+            })?;
 
-            debug!("body: {body}");
+            info!("Winners: {winners:02x?}");
 
-            // query the indexer
-            let resp = http_post!(indexer_endpoint, body, headers);
+            Ok(winners)
+        }
+
+        fn inner_get_tiered_winners(
+            &self,
+            raffle_id: RaffleId,
+            numbers: &[Number],
+            nb_numbers: u8,
+            nb_matching_numbers: &[(u8, u8)],
+            draw_mode: DrawMode,
+        ) -> Result<Vec<(u8, u8, Vec<AccountId>)>> {
+            info!(
+                "Request received to get the winners for raffle id {raffle_id}, numbers {numbers:?} and tiers {nb_matching_numbers:?}"
+            );
 
-            // check the result
-            if resp.status_code != 200 {
-                ink::env::debug_println!("status code {}", resp.status_code);
-                return Err(ContractError::HttpRequestFailed);
+            if numbers.is_empty() {
+                return Err(ContractError::NoNumber);
             }
 
-            // parse the result
-            let result: IndexerResponse = serde_json_core::from_slice(resp.body.as_slice())
-                .or(Err(ContractError::InvalidResponseBody))?
-                .0;
+            // build the filter
+            let filter = format!(
+                r#"filter:{{numRaffle:{{equalTo:\"{}\"}}}}"#,
+                raffle_id
+            );
 
-            // add the winners
-            let mut winners = Vec::new();
-            for w in result.data.participations.nodes.iter() {
-                // build the accountId from the string address
-                let account_id = AccountId32::from_ss58check(w.accountId)
-                    .or(Err(ContractError::InvalidSs58Address))?;
-                let address_hex: [u8; 32] = scale::Encode::encode(&account_id)
-                    .try_into()
-                    .or(Err(ContractError::InvalidKeyLength))?;
-                winners.push(AccountId::from(address_hex));
-            }
+            // the first numbers are the main numbers, the next ones are the bonus numbers
+            let (numbers, bonus_numbers) =
+                numbers.split_at((nb_numbers as usize).min(numbers.len()));
+
+            // add the winners in the tier matching their numbers (an account is added once per tier)
+            let mut tiered_winners: Vec<(u8, u8, Vec<AccountId>)> = nb_matching_numbers
+                .iter()
+                .map(|(nb, nb_bonus)| (*nb, *nb_bonus, Vec::new()))
+                .collect();
+            let fields = "accountId numbers";
+            self.query_participations(&filter, fields, |account_id, participation_numbers| {
+                let (participation_numbers, participation_bonus_numbers) = participation_numbers
+                    .split_at((nb_numbers as usize).min(participation_numbers.len()));
+                let nb_matching = count_matching(draw_mode, participation_numbers, numbers);
+                let nb_matching_bonus =
+                    count_matching(draw_mode, participation_bonus_numbers, bonus_numbers);
+                if let Some((_, _, winners)) =
+                    tiered_winners.iter_mut().find(|(nb, nb_bonus, _)| {
+                        *nb as usize == nb_matching && *nb_bonus as usize == nb_matching_bonus
+                    })
+                {
+                    if !winners.contains(&account_id) {
+                        winners.push(account_id);
+                    }
+                }
+            })?;
 
-            info!("Winners: {winners:02x?}");
+            info!("Tiered winners: {tiered_winners:02x?}");
 
-            Ok(winners)
+            Ok(tiered_winners)
+        }
+
+        /// Query the participations in the indexer and call the function for each participation.
+        /// The participations are fetched page by page until the last one
+        fn query_participations<F>(&self, filter: &str, fields: &str, mut f: F) -> Result<()>
+        where
+            F: FnMut(AccountId, &[Number]),
+        {
+            // check if the endpoint is configured
+            let indexer_endpoint = self.ensure_indexer_configured()?;
+
+            let mut offset = 0;
+            loop {
+                // build the body (sorted by id to get stable pages)
+                let body = format!(
+                    r#"{{"query" : "{{participations(first:{},offset:{},orderBy:ID_ASC,{}){{ nodes {{ {} }} }} }}"}}"#,
+                    PARTICIPATIONS_PAGE_SIZE, offset, filter, fields
+                );
+
+                // build the headers
+                let headers = alloc::vec![
+                    ("Content-Type".into(), "application/json".into()),
+                    ("Accept".into(), "application/json".into())
+                ];
+
+                debug!("body: {body}");
+
+                // query the indexer
+                let resp = http_post!(indexer_endpoint, body, headers);
+
+                // check the result
+                if resp.status_code != 200 {
+                    ink::env::debug_println!("status code {}", resp.status_code);
+                    return Err(ContractError::HttpRequestFailed);
+                }
+
+                // parse the result
+                let result: IndexerResponse = serde_json_core::from_slice(resp.body.as_slice())
+                    .or(Err(ContractError::InvalidResponseBody))?
+                    .0;
+
+                let nodes = result.data.participations.nodes;
+                for p in nodes.iter() {
+                    // build the accountId from the string address
+                    let account_id = AccountId32::from_ss58check(p.accountId)
+                        .or(Err(ContractError::InvalidSs58Address))?;
+                    let address_hex: [u8; 32] = scale::Encode::encode(&account_id)
+                        .try_into()
+                        .or(Err(ContractError::InvalidKeyLength))?;
+                    f(AccountId::from(address_hex), &p.numbers);
+                }
+
+                // a partial page is the last one
+                if nodes.len() < PARTICIPATIONS_PAGE_SIZE {
+                    return Ok(());
+                }
+                offset += nodes.len();
+            }
         }
 
         /// Returns BadOrigin error if the caller is not the owner
@@ -651,13 +890,24 @@ mod lotto_draw {
 
             let lotto = init_contract();
 
+            let game_id = 1;
             let raffle_id = 1;
             let nb_numbers = 5;
             let smallest_number = 1;
             let biggest_number = 50;
 
             let result = lotto
-                .inner_get_numbers(raffle_id, nb_numbers, smallest_number, biggest_number)
+                .inner_get_numbers(
+                    game_id,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
+                )
                 .unwrap();
             assert_eq!(nb_numbers as usize, result.len());
             for &n in result.iter() {
@@ -675,13 +925,24 @@ mod lotto_draw {
 
             let lotto = init_contract();
 
+            let game_id = 1;
             let raffle_id = 1;
             let nb_numbers = 5;
             let smallest_number = 1;
             let biggest_number = 5;
 
             let result = lotto
-                .inner_get_numbers(raffle_id, nb_numbers, smallest_number, biggest_number)
+                .inner_get_numbers(
+                    game_id,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
+                )
                 .unwrap();
             assert_eq!(nb_numbers as usize, result.len());
             for &n in result.iter() {
@@ -707,14 +968,34 @@ mod lotto_draw {
 
             for i in 0..100 {
                 let result = lotto
-                    .inner_get_numbers(i, nb_numbers, smallest_number, biggest_number)
+                    .inner_get_numbers(
+                        1,
+                        i,
+                        &DrawParams {
+                            nb_numbers,
+                            smallest_number,
+                            biggest_number,
+                            bonus: None,
+                            draw_mode: DrawMode::Unordered,
+                        },
+                    )
                     .unwrap();
                 // this result must be different from the previous ones
                 results.iter().for_each(|r| assert_ne!(result, *r));
 
                 // same request message means same result
                 let result_2 = lotto
-                    .inner_get_numbers(i, nb_numbers, smallest_number, biggest_number)
+                    .inner_get_numbers(
+                        1,
+                        i,
+                        &DrawParams {
+                            nb_numbers,
+                            smallest_number,
+                            biggest_number,
+                            bonus: None,
+                            draw_mode: DrawMode::Unordered,
+                        },
+                    )
                     .unwrap();
                 assert_eq!(result, result_2);
 
@@ -722,6 +1003,207 @@ mod lotto_draw {
             }
         }
 
+        #[ink::test]
+        fn test_with_different_games() {
+            let _ = env_logger::try_init();
+            pink_extension_runtime::mock_ext::mock_all_ext();
+
+            let lotto = init_contract();
+
+            let raffle_id = 1;
+            let nb_numbers = 5;
+            let smallest_number = 1;
+            let biggest_number = 50;
+
+            let result_1 = lotto
+                .inner_get_numbers(
+                    1,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
+                )
+                .unwrap();
+            let result_2 = lotto
+                .inner_get_numbers(
+                    2,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
+                )
+                .unwrap();
+            // same raffle id in another game means another result
+            assert_ne!(result_1, result_2);
+        }
+
+        #[ink::test]
+        fn test_get_numbers_with_bonus() {
+            let _ = env_logger::try_init();
+            pink_extension_runtime::mock_ext::mock_all_ext();
+
+            let lotto = init_contract();
+
+            let game_id = 1;
+            let raffle_id = 1;
+            let nb_numbers = 5;
+            let smallest_number = 1;
+            let biggest_number = 50;
+            let bonus = Some((2, 1, 12));
+
+            let result = lotto
+                .inner_get_numbers_with_bonus(
+                    game_id,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus,
+                        draw_mode: DrawMode::Unordered,
+                    },
+                )
+                .unwrap();
+            assert_eq!(7, result.len());
+            for &n in result[..5].iter() {
+                assert!(n >= smallest_number);
+                assert!(n <= biggest_number);
+            }
+            for &n in result[5..].iter() {
+                assert!(n >= 1);
+                assert!(n <= 12);
+            }
+
+            // the bonus numbers do not change the main numbers
+            let numbers = lotto
+                .inner_get_numbers(
+                    game_id,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
+                )
+                .unwrap();
+            assert_eq!(numbers, result[..5]);
+
+            assert_eq!(
+                Ok(true),
+                lotto.inner_verify_numbers(
+                    game_id,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus,
+                        draw_mode: DrawMode::Unordered,
+                    },
+                    result.clone()
+                )
+            );
+
+            ink::env::debug_println!("random numbers with bonus: {result:?}");
+        }
+
+        #[ink::test]
+        fn test_get_ordered_numbers() {
+            let _ = env_logger::try_init();
+            pink_extension_runtime::mock_ext::mock_all_ext();
+
+            let lotto = init_contract();
+
+            let game_id = 1;
+            let raffle_id = 1;
+            // pick-4 game: 4 digits between 0 and 9, the digits can be repeated
+            let nb_numbers = 4;
+            let smallest_number = 0;
+            let biggest_number = 9;
+
+            let result = lotto
+                .inner_get_numbers(
+                    game_id,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Ordered,
+                    },
+                )
+                .unwrap();
+            assert_eq!(nb_numbers as usize, result.len());
+            for &n in result.iter() {
+                assert!(n <= biggest_number);
+            }
+
+            assert_eq!(
+                Ok(true),
+                lotto.inner_verify_numbers(
+                    game_id,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Ordered,
+                    },
+                    result.clone()
+                )
+            );
+
+            // the order matters
+            let mut reversed = result.clone();
+            reversed.reverse();
+            if reversed != result {
+                assert_eq!(
+                    Ok(false),
+                    lotto.inner_verify_numbers(
+                        game_id,
+                        raffle_id,
+                        &DrawParams {
+                            nb_numbers,
+                            smallest_number,
+                            biggest_number,
+                            bonus: None,
+                            draw_mode: DrawMode::Ordered,
+                        },
+                        reversed
+                    )
+                );
+            }
+
+            ink::env::debug_println!("ordered numbers: {result:?}");
+        }
+
+        #[ink::test]
+        fn test_draw_quick_pick_seed() {
+            let _ = env_logger::try_init();
+            pink_extension_runtime::mock_ext::mock_all_ext();
+
+            let lotto = init_contract();
+
+            let seed_1 = lotto.inner_draw_quick_pick_seed(1, 1).unwrap();
+            // same request message means same seed
+            assert_eq!(seed_1, lotto.inner_draw_quick_pick_seed(1, 1).unwrap());
+            // another raffle or another game means another seed
+            assert_ne!(seed_1, lotto.inner_draw_quick_pick_seed(1, 2).unwrap());
+            assert_ne!(seed_1, lotto.inner_draw_quick_pick_seed(2, 1).unwrap());
+        }
+
         #[ink::test]
         fn test_verify_numbers() {
             let _ = env_logger::try_init();
@@ -729,22 +1211,38 @@ mod lotto_draw {
 
             let lotto = init_contract();
 
+            let game_id = 1;
             let raffle_id = 1;
             let nb_numbers = 5;
             let smallest_number = 1;
             let biggest_number = 50;
 
             let numbers = lotto
-                .inner_get_numbers(raffle_id, nb_numbers, smallest_number, biggest_number)
+                .inner_get_numbers(
+                    game_id,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
+                )
                 .unwrap();
 
             assert_eq!(
                 Ok(true),
                 lotto.inner_verify_numbers(
+                    game_id,
                     raffle_id,
-                    nb_numbers,
-                    smallest_number,
-                    biggest_number,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
                     numbers.clone()
                 )
             );
@@ -752,10 +1250,15 @@ mod lotto_draw {
             assert_eq!(
                 Ok(false),
                 lotto.inner_verify_numbers(
+                    game_id,
                     raffle_id + 1,
-                    nb_numbers,
-                    smallest_number,
-                    biggest_number,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
                     numbers.clone()
                 )
             );
@@ -768,22 +1271,38 @@ mod lotto_draw {
 
             let mut lotto = init_contract();
 
+            let game_id = 1;
             let raffle_id = 1;
             let nb_numbers = 5;
             let smallest_number = 1;
             let biggest_number = 50;
 
             let numbers = lotto
-                .inner_get_numbers(raffle_id, nb_numbers, smallest_number, biggest_number)
+                .inner_get_numbers(
+                    game_id,
+                    raffle_id,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
+                )
                 .unwrap();
 
             assert_eq!(
                 Ok(true),
                 lotto.inner_verify_numbers(
+                    game_id,
                     raffle_id,
-                    nb_numbers,
-                    smallest_number,
-                    biggest_number,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
                     numbers.clone()
                 )
             );
@@ -804,10 +1323,15 @@ mod lotto_draw {
             assert_eq!(
                 Ok(false),
                 lotto.inner_verify_numbers(
+                    game_id,
                     raffle_id,
-                    nb_numbers,
-                    smallest_number,
-                    biggest_number,
+                    &DrawParams {
+                        nb_numbers,
+                        smallest_number,
+                        biggest_number,
+                        bonus: None,
+                        draw_mode: DrawMode::Unordered,
+                    },
                     numbers.clone()
                 )
             );
@@ -827,6 +1351,30 @@ mod lotto_draw {
             ink::env::debug_println!("winners: {winners:?}");
         }
 
+        #[ink::test]
+        fn test_get_tiered_winners() {
+            let _ = env_logger::try_init();
+            pink_extension_runtime::mock_ext::mock_all_ext();
+
+            let lotto = init_contract();
+
+            let draw_num = 2;
+            let numbers = vec![15, 1, 44, 28];
+            let nb_matching_numbers = vec![(4, 0), (3, 0), (2, 0)];
+
+            let tiered_winners = lotto
+                .inner_get_tiered_winners(
+                    draw_num,
+                    &numbers,
+                    4,
+                    &nb_matching_numbers,
+                    DrawMode::Unordered,
+                )
+                .unwrap();
+            assert_eq!(nb_matching_numbers.len(), tiered_winners.len());
+            ink::env::debug_println!("tiered winners: {tiered_winners:?}");
+        }
+
         #[ink::test]
         fn test_no_winner() {
             let _ = env_logger::try_init();
@@ -878,18 +1426,19 @@ mod lotto_draw {
             //Request received for raffle 6 - draw 4 numbers between 1 and 50
             // Numbers: [4, 49, 41, 16]
 
+            let game_id = 1;
             let raffle_id = 6;
             let numbers = vec![4, 49, 41, 16];
 
             let response = LottoResponseMessage {
-                request: LottoRequestMessage {raffle_id, request: Request::DrawNumbers(4, 1, 50)},
+                request: LottoRequestMessage {game_id, raffle_id, request: Request::DrawNumbers(DrawParams {nb_numbers: 4, smallest_number: 1, biggest_number: 50, bonus: None, draw_mode: DrawMode::Unordered})},
                 response: Response::Numbers(numbers.clone()),
             };
             let encoded_response = response.encode();
             ink::env::debug_println!("Reply response numbers: {encoded_response:02x?}");
 
             let response = LottoResponseMessage {
-                request: LottoRequestMessage {raffle_id, request: Request::CheckWinners(numbers)},
+                request: LottoRequestMessage {game_id, raffle_id, request: Request::CheckWinners(numbers)},
                 response: Response::Winners(vec![]),
             };
             let encoded_response = response.encode();
@@ -919,11 +1468,11 @@ mod lotto_draw {
 
         #[ink::test]
         fn decode_message() {
-            let encoded_message : Vec<u8> = hex::decode("0600000001100400310029001000").expect("hex decode failed");
+            let encoded_message : Vec<u8> = hex::decode("010000000600000001100400310029001000").expect("hex decode failed");
             let message = LottoRequestMessage::decode(&mut encoded_message.as_slice());
             ink::env::debug_println!("message: {message:?}");
 
-            let encoded_message : Vec<u8> = hex::decode("07000000000401003200").expect("hex decode failed");
+            let encoded_message : Vec<u8> = hex::decode("01000000070000000004010032000000").expect("hex decode failed");
             let message = LottoRequestMessage::decode(&mut encoded_message.as_slice());
             ink::env::debug_println!("message: {message:?}");
 